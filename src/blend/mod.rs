@@ -1,21 +1,22 @@
+//! Functions for blending in HCT and CAM16.
+
 use crate::{
     hct::{Cam16, Hct},
     utils::{color_utils, math_utils},
 };
 
-/// Functions for blending in HCT and CAM16.
-
 /// Shifts `design_color`'s hue towards `source_color`'s, creating a slightly
 /// warmer/coolor variant of `design_color`. Hue will shift up to 15 degrees.
 pub fn harmonize(design_color: u32, source_color: u32) -> u32 {
     let from_hct = Hct::from_int(design_color);
     let to_hct = Hct::from_int(source_color);
-    let difference_degrees = math_utils::calculate_difference_degrees(from_hct.hue, to_hct.hue);
+    let difference_degrees = math_utils::calculate_difference_degrees(from_hct.hue(), to_hct.hue());
     let rotation_degrees = (difference_degrees * 0.5).min(15.0);
     let output_hue = math_utils::sanitize_degrees_double(
-        from_hct.hue + rotation_degrees * math_utils::rotation_direction(from_hct.hue, to_hct.hue),
+        from_hct.hue()
+            + rotation_degrees * math_utils::rotation_direction(from_hct.hue(), to_hct.hue()),
     );
-    Hct::new(output_hue, from_hct.chroma, from_hct.tone).to_int()
+    Hct::new(output_hue, from_hct.chroma(), from_hct.tone()).to_int()
 }
 
 /// Blends `from`'s hue in HCT towards `to`'s hue.
@@ -63,72 +64,72 @@ mod test {
     #[test]
     fn red_to_blue() {
         let answer = harmonize(RED, BLUE);
-        assert_eq!(answer, 0xffFB0057);
+        assert_eq!(answer, 0xfffb0057);
     }
 
     #[test]
     fn red_to_green() {
         let answer = harmonize(RED, GREEN);
-        assert_eq!(answer, 0xffD85600);
+        assert_eq!(answer, 0xffd85600);
     }
 
     #[test]
     fn red_to_yellow() {
         let answer = harmonize(RED, YELLOW);
-        assert_eq!(answer, 0xffD85600);
+        assert_eq!(answer, 0xffd85600);
     }
 
     #[test]
     fn blue_to_green() {
         let answer = harmonize(BLUE, GREEN);
-        assert_eq!(answer, 0xff0047A3);
+        assert_eq!(answer, 0xff0047a3);
     }
 
     #[test]
     fn blue_to_red() {
         let answer = harmonize(BLUE, RED);
-        assert_eq!(answer, 0xff5700DC);
+        assert_eq!(answer, 0xff5700dc);
     }
 
     #[test]
     fn blue_to_yellow() {
         let answer = harmonize(BLUE, YELLOW);
-        assert_eq!(answer, 0xff0047A3);
+        assert_eq!(answer, 0xff0047a3);
     }
 
     #[test]
     fn green_to_blue() {
         let answer = harmonize(GREEN, BLUE);
-        assert_eq!(answer, 0xff00FC94);
+        assert_eq!(answer, 0xff00fc94);
     }
 
     #[test]
     fn green_to_red() {
         let answer = harmonize(GREEN, RED);
-        assert_eq!(answer, 0xffB1F000);
+        assert_eq!(answer, 0xffb1f000);
     }
 
     #[test]
     fn green_to_yellow() {
         let answer = harmonize(GREEN, YELLOW);
-        assert_eq!(answer, 0xffB1F000);
+        assert_eq!(answer, 0xffb1f000);
     }
 
     #[test]
     fn yellow_to_blue() {
         let answer = harmonize(YELLOW, BLUE);
-        assert_eq!(answer, 0xffEBFFBA);
+        assert_eq!(answer, 0xffebffba);
     }
 
     #[test]
     fn yellow_to_green() {
         let answer = harmonize(YELLOW, GREEN);
-        assert_eq!(answer, 0xffEBFFBA);
+        assert_eq!(answer, 0xffebffba);
     }
 
     #[test]
     fn yellow_to_red() {
         let answer = harmonize(YELLOW, RED);
-        assert_eq!(answer, 0xffFFF6E3);
+        assert_eq!(answer, 0xfffff6e3);
     }
 }
@@ -0,0 +1,82 @@
+use crate::hct::Hct;
+
+/// Check and/or fix universally disliked colors.
+///
+/// Color science studies of color preference indicate universal distaste for
+/// dark yellow-greens, and also show this is correlated to distate for
+/// biological waste and rotting food.
+///
+/// See Palmer and Schloss, 2010 or Schloss and Palmer's Chapter 21 in Handbook
+/// of Color Psychology (2015).
+pub struct DislikeAnalyzer;
+
+impl DislikeAnalyzer {
+    /// Returns true if `hct` is disliked.
+    ///
+    /// Disliked is defined as a dark yellow-green that is not neutral.
+    pub fn is_disliked(hct: Hct) -> bool {
+        let hue_passes = hct.hue().round() >= 90.0 && hct.hue().round() <= 111.0;
+        let chroma_passes = hct.chroma().round() > 16.0;
+        let tone_passes = hct.tone().round() < 65.0;
+
+        hue_passes && chroma_passes && tone_passes
+    }
+
+    /// If `hct` is disliked, lighten it to make it likable.
+    pub fn fix_if_disliked(hct: Hct) -> Hct {
+        let mut hct = hct;
+        if DislikeAnalyzer::is_disliked(hct) {
+            hct.set_tone(70.0);
+        }
+
+        hct
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hct::Hct;
+
+    use super::DislikeAnalyzer;
+
+    #[test]
+    fn monk_skin_tone_scale_colors_liked() {
+        // From https://skintone.google#/get-started
+        let monk_skin_tone_scale_colors = [
+            0xfff6ede4, 0xfff3e7db, 0xfff7ead0, 0xffeadaba, 0xffd7bd96, 0xffa07e56, 0xff825c43,
+            0xff604134, 0xff3a312a, 0xff292420,
+        ];
+        for color in monk_skin_tone_scale_colors {
+            assert!(!DislikeAnalyzer::is_disliked(Hct::from_int(color)));
+        }
+    }
+
+    #[test]
+    fn bile_colors_disliked() {
+        let unlikable = [0xff95884b, 0xff716b40, 0xffb08e00, 0xff4c4308, 0xff464521];
+        for color in unlikable {
+            assert!(DislikeAnalyzer::is_disliked(Hct::from_int(color)));
+        }
+    }
+
+    #[test]
+    fn bile_colors_became_likable() {
+        let unlikable = [0xff95884b, 0xff716b40, 0xffb08e00, 0xff4c4308, 0xff464521];
+        for color in unlikable {
+            let hct = Hct::from_int(color);
+            assert!(DislikeAnalyzer::is_disliked(hct));
+            let likable = DislikeAnalyzer::fix_if_disliked(hct);
+            assert!(!DislikeAnalyzer::is_disliked(likable));
+        }
+    }
+
+    #[test]
+    fn tone_67_not_disliked() {
+        let color = Hct::new(100.0, 50.0, 67.0);
+        assert!(!DislikeAnalyzer::is_disliked(color));
+        assert_eq!(
+            DislikeAnalyzer::fix_if_disliked(color).to_int(),
+            color.to_int()
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::utils::math_utils::lerp;
+
+/// A class containing a value that changes with the contrast level.
+///
+/// Usually represents the contrast requirements for a dynamic color on its
+/// background. The four values correspond to values for contrast levels -1.0,
+/// 0.0, 0.5, and 1.0, respectively.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ContrastCurve {
+    /// Value for contrast level -1.0
+    pub low: f64,
+    /// Value for contrast level 0.0
+    pub normal: f64,
+    /// Value for contrast level 0.5
+    pub medium: f64,
+    /// Value for contrast level 1.0
+    pub high: f64,
+}
+
+impl ContrastCurve {
+    /// Creates a [`ContrastCurve`] object.
+    pub fn new(low: f64, normal: f64, medium: f64, high: f64) -> ContrastCurve {
+        ContrastCurve {
+            low,
+            normal,
+            medium,
+            high,
+        }
+    }
+
+    /// Returns the value at a given contrast level.
+    ///
+    /// `contrast_level` The contrast level. 0.0 is the default (normal); -1.0
+    /// is the lowest; 1.0 is the highest.
+    pub fn get(&self, contrast_level: f64) -> f64 {
+        if contrast_level <= -1.0 {
+            self.low
+        } else if contrast_level < 0.0 {
+            lerp(self.low, self.normal, contrast_level + 1.0)
+        } else if contrast_level < 0.5 {
+            lerp(self.normal, self.medium, contrast_level / 0.5)
+        } else if contrast_level < 1.0 {
+            lerp(self.medium, self.high, (contrast_level - 0.5) / 0.5)
+        } else {
+            self.high
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx_eq::assert_approx_eq;
+
+    use super::ContrastCurve;
+
+    #[test]
+    fn interpolates_between_levels() {
+        let curve = ContrastCurve::new(1.0, 3.0, 7.0, 11.0);
+
+        assert_eq!(curve.get(-2.0), 1.0);
+        assert_eq!(curve.get(-1.0), 1.0);
+        assert_approx_eq!(curve.get(-0.5), 2.0);
+        assert_eq!(curve.get(0.0), 3.0);
+        assert_approx_eq!(curve.get(0.25), 5.0);
+        assert_eq!(curve.get(0.5), 7.0);
+        assert_approx_eq!(curve.get(0.75), 9.0);
+        assert_eq!(curve.get(1.0), 11.0);
+        assert_eq!(curve.get(2.0), 11.0);
+    }
+}
@@ -0,0 +1,499 @@
+use crate::{
+    hct::Hct,
+    palette::TonalPalette,
+    scheme::DynamicScheme,
+    utils::{
+        contrast::{darker, darker_unsafe, lighter, lighter_unsafe, ratio_of_tones},
+        math_utils::clamp_double,
+    },
+};
+
+use super::{ContrastCurve, ToneDeltaPair, TonePolarity};
+
+/// A color that adjusts itself based on UI state provided by
+/// [`DynamicScheme`].
+///
+/// Colors without backgrounds do not change tone when contrast changes. Colors
+/// with backgrounds become closer to their background as contrast lowers, and
+/// further when contrast increases.
+///
+/// Prefer using the roles provided by
+/// [`MaterialDynamicColors`](super::MaterialDynamicColors) rather than
+/// constructing colors by hand.
+#[derive(Debug, Clone)]
+pub struct DynamicColor {
+    /// The name of the dynamic color.
+    pub name: String,
+    /// Function that provides a [`TonalPalette`] given a [`DynamicScheme`]. A
+    /// [`TonalPalette`] is defined by a hue and chroma, so this replaces the
+    /// need to specify hue/chroma. By providing a tonal palette, when contrast
+    /// adjustments are made, intended chroma can be preserved.
+    pub palette: fn(&DynamicScheme) -> &TonalPalette,
+    /// Function that provides a tone given a [`DynamicScheme`].
+    pub tone: fn(&DynamicScheme) -> f64,
+    /// Whether this dynamic color is a background, with some other color as
+    /// the foreground.
+    pub is_background: bool,
+    /// The background of the dynamic color (as a function of a
+    /// [`DynamicScheme`]), if it exists.
+    pub background: Option<fn(&DynamicScheme) -> DynamicColor>,
+    /// A second background of the dynamic color (as a function of a
+    /// [`DynamicScheme`]), if it exists.
+    pub second_background: Option<fn(&DynamicScheme) -> DynamicColor>,
+    /// A [`ContrastCurve`] object specifying how its contrast against its
+    /// background should behave in various contrast levels options.
+    pub contrast_curve: Option<ContrastCurve>,
+    /// A [`ToneDeltaPair`] object specifying a tone delta constraint between
+    /// two colors. One of them must be the color being constructed.
+    pub tone_delta_pair: Option<fn(&DynamicScheme) -> ToneDeltaPair>,
+}
+
+impl DynamicColor {
+    /// Creates a [`DynamicColor`] with no background, contrast curve or tone
+    /// delta constraint.
+    ///
+    /// Use struct update syntax to fill in the remaining fields:
+    ///
+    /// ```
+    /// use material3::dynamiccolor::{ContrastCurve, DynamicColor, MaterialDynamicColors};
+    ///
+    /// let on_brand = DynamicColor {
+    ///     background: Some(|_| MaterialDynamicColors::primary_container()),
+    ///     contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+    ///     ..DynamicColor::from_palette(
+    ///         "on_brand",
+    ///         |s| &s.primary_palette,
+    ///         |s| if s.is_dark { 90.0 } else { 10.0 },
+    ///     )
+    /// };
+    /// ```
+    pub fn from_palette(
+        name: &str,
+        palette: fn(&DynamicScheme) -> &TonalPalette,
+        tone: fn(&DynamicScheme) -> f64,
+    ) -> DynamicColor {
+        DynamicColor {
+            name: name.to_string(),
+            palette,
+            tone,
+            is_background: false,
+            background: None,
+            second_background: None,
+            contrast_curve: None,
+            tone_delta_pair: None,
+        }
+    }
+
+    /// Returns the ARGB representation of this color in `scheme`.
+    pub fn get_argb(&self, scheme: &DynamicScheme) -> u32 {
+        self.get_hct(scheme).to_int()
+    }
+
+    /// Returns the HCT representation of this color in `scheme`.
+    pub fn get_hct(&self, scheme: &DynamicScheme) -> Hct {
+        let tone = self.get_tone(scheme);
+        (self.palette)(scheme).get_hct(tone)
+    }
+
+    /// Returns the tone of this color in `scheme`, after all contrast
+    /// requirements have been applied.
+    pub fn get_tone(&self, scheme: &DynamicScheme) -> f64 {
+        let decreasing_contrast = scheme.contrast_level < 0.0;
+
+        // Case 1: dual foreground, pair of colors with delta constraint.
+        if let Some(tone_delta_pair) = self.tone_delta_pair {
+            let pair = tone_delta_pair(scheme);
+            let role_a = pair.role_a;
+            let role_b = pair.role_b;
+            let delta = pair.delta;
+            let polarity = pair.polarity;
+            let stay_together = pair.stay_together;
+
+            let background = (self
+                .background
+                .expect("a color with a tone delta pair must have a background"))(
+                scheme
+            );
+            let bg_tone = background.get_tone(scheme);
+
+            let a_is_nearer = polarity == TonePolarity::Nearer
+                || (polarity == TonePolarity::Lighter && !scheme.is_dark)
+                || (polarity == TonePolarity::Darker && scheme.is_dark);
+            let (nearer, farther) = if a_is_nearer {
+                (role_a, role_b)
+            } else {
+                (role_b, role_a)
+            };
+            let am_nearer = self.name == nearer.name;
+            let expansion_dir = if scheme.is_dark { 1.0 } else { -1.0 };
+
+            // 1st round: solve to min, each
+            let n_contrast = nearer
+                .contrast_curve
+                .expect("a color with a tone delta pair must have a contrast curve")
+                .get(scheme.contrast_level);
+            let f_contrast = farther
+                .contrast_curve
+                .expect("a color with a tone delta pair must have a contrast curve")
+                .get(scheme.contrast_level);
+
+            // If a color is good enough, it is not adjusted.
+            // Initial and adjusted tones for `nearer`
+            let n_initial_tone = (nearer.tone)(scheme);
+            let mut n_tone = if ratio_of_tones(bg_tone, n_initial_tone) >= n_contrast {
+                n_initial_tone
+            } else {
+                DynamicColor::foreground_tone(bg_tone, n_contrast)
+            };
+            // Initial and adjusted tones for `farther`
+            let f_initial_tone = (farther.tone)(scheme);
+            let mut f_tone = if ratio_of_tones(bg_tone, f_initial_tone) >= f_contrast {
+                f_initial_tone
+            } else {
+                DynamicColor::foreground_tone(bg_tone, f_contrast)
+            };
+
+            if decreasing_contrast {
+                // If decreasing contrast, adjust color to the "bare minimum"
+                // that satisfies contrast.
+                n_tone = DynamicColor::foreground_tone(bg_tone, n_contrast);
+                f_tone = DynamicColor::foreground_tone(bg_tone, f_contrast);
+            }
+
+            // If constraint is not satisfied, try another round.
+            if (f_tone - n_tone) * expansion_dir < delta {
+                // 2nd round: expand farther to match delta.
+                f_tone = clamp_double(0.0, 100.0, n_tone + delta * expansion_dir);
+                // If constraint is not satisfied, try another round.
+                if (f_tone - n_tone) * expansion_dir < delta {
+                    // 3rd round: contract nearer to match delta.
+                    n_tone = clamp_double(0.0, 100.0, f_tone - delta * expansion_dir);
+                }
+            }
+
+            // Avoids the 50-59 awkward zone.
+            if (50.0..60.0).contains(&n_tone) {
+                // If `nearer` is in the awkward zone, move it away, together with
+                // `farther`.
+                if expansion_dir > 0.0 {
+                    n_tone = 60.0;
+                    f_tone = f_tone.max(n_tone + delta * expansion_dir);
+                } else {
+                    n_tone = 49.0;
+                    f_tone = f_tone.min(n_tone + delta * expansion_dir);
+                }
+            } else if (50.0..60.0).contains(&f_tone) {
+                if stay_together {
+                    // Fixes both, to avoid two colors on opposite sides of the
+                    // "awkward zone".
+                    if expansion_dir > 0.0 {
+                        n_tone = 60.0;
+                        f_tone = f_tone.max(n_tone + delta * expansion_dir);
+                    } else {
+                        n_tone = 49.0;
+                        f_tone = f_tone.min(n_tone + delta * expansion_dir);
+                    }
+                } else {
+                    // Not required to stay together; fixes just one.
+                    f_tone = if expansion_dir > 0.0 { 60.0 } else { 49.0 };
+                }
+            }
+
+            // Returns `n_tone` if this color is `nearer`, otherwise `f_tone`.
+            return if am_nearer { n_tone } else { f_tone };
+        }
+
+        // Case 2: No contrast pair; just solve for itself.
+        let mut answer = (self.tone)(scheme);
+
+        let background = match self.background {
+            // No adjustment for colors with no background.
+            None => return answer,
+            Some(background) => background(scheme),
+        };
+
+        let bg_tone = background.get_tone(scheme);
+
+        let desired_ratio = self
+            .contrast_curve
+            .expect("a color with a background must have a contrast curve")
+            .get(scheme.contrast_level);
+
+        // Don't "improve" what's good enough.
+        if ratio_of_tones(bg_tone, answer) < desired_ratio {
+            // Rough improvement.
+            answer = DynamicColor::foreground_tone(bg_tone, desired_ratio);
+        }
+
+        if decreasing_contrast {
+            answer = DynamicColor::foreground_tone(bg_tone, desired_ratio);
+        }
+
+        if self.is_background && (50.0..60.0).contains(&answer) {
+            // Must adjust
+            answer = if ratio_of_tones(49.0, bg_tone) >= desired_ratio {
+                49.0
+            } else {
+                60.0
+            };
+        }
+
+        if let Some(second_background) = self.second_background {
+            // Case 3: Adjust for dual backgrounds.
+            let bg_tone_1 = bg_tone;
+            let bg_tone_2 = second_background(scheme).get_tone(scheme);
+
+            let upper = bg_tone_1.max(bg_tone_2);
+            let lower = bg_tone_1.min(bg_tone_2);
+
+            if ratio_of_tones(upper, answer) >= desired_ratio
+                && ratio_of_tones(lower, answer) >= desired_ratio
+            {
+                return answer;
+            }
+
+            // The darkest light tone that satisfies the desired ratio,
+            // or -1 if such ratio cannot be reached.
+            let light_option = lighter(upper, desired_ratio);
+
+            // The lightest dark tone that satisfies the desired ratio,
+            // or -1 if such ratio cannot be reached.
+            let dark_option = darker(lower, desired_ratio);
+
+            // Tones suitable for the foreground.
+            let availables: Vec<f64> = [light_option, dark_option]
+                .into_iter()
+                .filter(|option| *option != -1.0)
+                .collect();
+
+            let prefers_light = DynamicColor::tone_prefers_light_foreground(bg_tone_1)
+                || DynamicColor::tone_prefers_light_foreground(bg_tone_2);
+            if prefers_light {
+                return if light_option < 0.0 {
+                    100.0
+                } else {
+                    light_option
+                };
+            }
+            if availables.len() == 1 {
+                return availables[0];
+            }
+            return if dark_option < 0.0 { 0.0 } else { dark_option };
+        }
+
+        answer
+    }
+
+    /// Given a background tone, find a foreground tone, while ensuring they
+    /// reach a contrast ratio that is as close to `ratio` as possible.
+    ///
+    /// `bg_tone` Tone in HCT. Range is 0 to 100, undefined behavior when it
+    /// falls outside that range.
+    ///
+    /// `ratio` The contrast ratio desired between `bg_tone` and the return
+    /// value.
+    pub fn foreground_tone(bg_tone: f64, ratio: f64) -> f64 {
+        let lighter_tone = lighter_unsafe(bg_tone, ratio);
+        let darker_tone = darker_unsafe(bg_tone, ratio);
+        let lighter_ratio = ratio_of_tones(lighter_tone, bg_tone);
+        let darker_ratio = ratio_of_tones(darker_tone, bg_tone);
+        let prefer_lighter = DynamicColor::tone_prefers_light_foreground(bg_tone);
+
+        if prefer_lighter {
+            // "Negligible difference" handles an edge case where the initial
+            // contrast ratio is high (ex. 13.0), and the ratio passed to the
+            // function is that high ratio, and both the lighter and darker
+            // ratio fails to pass that ratio.
+            //
+            // This was observed with Tonal Spot's On Primary Container turning
+            // black momentarily between high and max contrast in light mode.
+            // PC's standard tone was T90, OPC's was T10, it was light mode, and
+            // the contrast value was 0.6568521221032331.
+            let negligible_difference = (lighter_ratio - darker_ratio).abs() < 0.1
+                && lighter_ratio < ratio
+                && darker_ratio < ratio;
+            if lighter_ratio >= ratio || lighter_ratio >= darker_ratio || negligible_difference {
+                lighter_tone
+            } else {
+                darker_tone
+            }
+        } else if darker_ratio >= ratio || darker_ratio >= lighter_ratio {
+            darker_tone
+        } else {
+            lighter_tone
+        }
+    }
+
+    /// Adjust a tone such that white has 4.5 contrast, if the tone is
+    /// reasonably close to supporting it.
+    pub fn enable_light_foreground(tone: f64) -> f64 {
+        if DynamicColor::tone_prefers_light_foreground(tone)
+            && !DynamicColor::tone_allows_light_foreground(tone)
+        {
+            return 49.0;
+        }
+        tone
+    }
+
+    /// Returns whether `tone` prefers a light foreground.
+    ///
+    /// People prefer white foregrounds on ~T60-70. Observed over time, and also
+    /// by Andrew Somers during research for APCA.
+    ///
+    /// T60 used as to create the smallest discontinuity possible when skipping
+    /// down to T49 in order to ensure light foregrounds.
+    ///
+    /// Since `tone` is rounded, T59.5 and up is treated as not preferring a
+    /// light foreground.
+    pub fn tone_prefers_light_foreground(tone: f64) -> bool {
+        tone.round() < 60.0
+    }
+
+    /// Returns whether `tone` can reach a contrast ratio of 4.5 with a lighter
+    /// color.
+    pub fn tone_allows_light_foreground(tone: f64) -> bool {
+        tone.round() <= 49.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dynamiccolor::{DynamicColor, MaterialDynamicColors, Variant},
+        hct::Hct,
+        scheme::DynamicScheme,
+        utils::contrast::ratio_of_tones,
+    };
+
+    const SEED_COLORS: [u32; 4] = [0xffff0000, 0xffffff00, 0xff00ff00, 0xff0000ff];
+    const CONTRAST_LEVELS: [f64; 5] = [-1.0, -0.5, 0.0, 0.5, 1.0];
+
+    fn scheme(source: u32, variant: Variant, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::from_variant(Hct::from_int(source), variant, is_dark, contrast_level)
+    }
+
+    fn text_surface_pairs() -> Vec<(DynamicColor, DynamicColor)> {
+        vec![
+            (
+                MaterialDynamicColors::on_primary(),
+                MaterialDynamicColors::primary(),
+            ),
+            (
+                MaterialDynamicColors::on_primary_container(),
+                MaterialDynamicColors::primary_container(),
+            ),
+            (
+                MaterialDynamicColors::on_secondary(),
+                MaterialDynamicColors::secondary(),
+            ),
+            (
+                MaterialDynamicColors::on_secondary_container(),
+                MaterialDynamicColors::secondary_container(),
+            ),
+            (
+                MaterialDynamicColors::on_tertiary(),
+                MaterialDynamicColors::tertiary(),
+            ),
+            (
+                MaterialDynamicColors::on_tertiary_container(),
+                MaterialDynamicColors::tertiary_container(),
+            ),
+            (
+                MaterialDynamicColors::on_error(),
+                MaterialDynamicColors::error(),
+            ),
+            (
+                MaterialDynamicColors::on_error_container(),
+                MaterialDynamicColors::error_container(),
+            ),
+            (
+                MaterialDynamicColors::on_background(),
+                MaterialDynamicColors::background(),
+            ),
+            (
+                MaterialDynamicColors::on_surface_variant(),
+                MaterialDynamicColors::surface_bright(),
+            ),
+            (
+                MaterialDynamicColors::on_surface_variant(),
+                MaterialDynamicColors::surface_dim(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn constraint_conformance() {
+        for seed in SEED_COLORS {
+            for contrast_level in CONTRAST_LEVELS {
+                for is_dark in [false, true] {
+                    for variant in [Variant::TonalSpot, Variant::Fidelity, Variant::Monochrome] {
+                        let scheme = scheme(seed, variant, is_dark, contrast_level);
+                        for (foreground, background) in text_surface_pairs() {
+                            let foreground_tone = foreground.get_hct(&scheme).tone();
+                            let background_tone = background.get_hct(&scheme).tone();
+                            let contrast = ratio_of_tones(foreground_tone, background_tone);
+                            let minimum_requirement = if contrast_level >= 0.0 { 4.5 } else { 3.0 };
+                            assert!(
+                                contrast >= minimum_requirement,
+                                "Contrast {} is too low between foreground ({}; {}) and background ({}; {})",
+                                contrast,
+                                foreground.name,
+                                foreground_tone,
+                                background.name,
+                                background_tone
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_colors_in_non_monochrome_schemes() {
+        let scheme = scheme(0xffff0000, Variant::TonalSpot, true, 0.0);
+
+        assert_eq!(
+            MaterialDynamicColors::primary_fixed()
+                .get_hct(&scheme)
+                .tone()
+                .round(),
+            90.0
+        );
+        assert_eq!(
+            MaterialDynamicColors::primary_fixed_dim()
+                .get_hct(&scheme)
+                .tone()
+                .round(),
+            80.0
+        );
+        assert_eq!(
+            MaterialDynamicColors::on_primary_fixed()
+                .get_hct(&scheme)
+                .tone()
+                .round(),
+            10.0
+        );
+        assert_eq!(
+            MaterialDynamicColors::on_primary_fixed_variant()
+                .get_hct(&scheme)
+                .tone()
+                .round(),
+            30.0
+        );
+    }
+
+    #[test]
+    fn foreground_tone_prefers_light_on_dark_backgrounds() {
+        assert!(DynamicColor::foreground_tone(20.0, 4.5) > 20.0);
+        assert!(DynamicColor::foreground_tone(80.0, 4.5) < 80.0);
+        assert!(ratio_of_tones(DynamicColor::foreground_tone(40.0, 4.5), 40.0) >= 4.5);
+    }
+
+    #[test]
+    fn enable_light_foreground_skips_awkward_zone() {
+        assert_eq!(DynamicColor::enable_light_foreground(55.0), 49.0);
+        assert_eq!(DynamicColor::enable_light_foreground(40.0), 40.0);
+        assert_eq!(DynamicColor::enable_light_foreground(70.0), 70.0);
+    }
+}
@@ -0,0 +1,1133 @@
+use crate::{dislike::DislikeAnalyzer, palette::TonalPalette, scheme::DynamicScheme};
+
+use super::{ContrastCurve, DynamicColor, ToneDeltaPair, TonePolarity, Variant};
+
+fn is_fidelity(scheme: &DynamicScheme) -> bool {
+    scheme.variant == Variant::Fidelity || scheme.variant == Variant::Content
+}
+
+fn is_monochrome(scheme: &DynamicScheme) -> bool {
+    scheme.variant == Variant::Monochrome
+}
+
+fn find_desired_chroma_by_tone(palette: &TonalPalette, tone: f64, by_decreasing_tone: bool) -> f64 {
+    let chroma = palette.chroma();
+    let mut answer = tone;
+
+    let mut closest_to_chroma = palette.get_hct(tone);
+    if closest_to_chroma.chroma() < chroma {
+        let mut chroma_peak = closest_to_chroma.chroma();
+        while closest_to_chroma.chroma() < chroma {
+            answer += if by_decreasing_tone { -1.0 } else { 1.0 };
+            let potential_solution = palette.get_hct(answer);
+            if chroma_peak > potential_solution.chroma() {
+                break;
+            }
+            if (potential_solution.chroma() - chroma).abs() < 0.4 {
+                break;
+            }
+
+            let potential_delta = (potential_solution.chroma() - chroma).abs();
+            let current_delta = (closest_to_chroma.chroma() - chroma).abs();
+            if potential_delta < current_delta {
+                closest_to_chroma = potential_solution;
+            }
+            chroma_peak = chroma_peak.max(potential_solution.chroma());
+        }
+    }
+
+    answer
+}
+
+/// Tokens, or named colors, in the Material Design system.
+///
+/// Each role is a [`DynamicColor`] that is resolved against a
+/// [`DynamicScheme`] when it is requested.
+pub struct MaterialDynamicColors;
+
+impl MaterialDynamicColors {
+    pub const CONTENT_ACCENT_TONE_DELTA: f64 = 15.0;
+
+    /// The surface that is furthest from the text in the scheme's brightness,
+    /// used as the background of most foreground roles.
+    pub fn highest_surface(scheme: &DynamicScheme) -> DynamicColor {
+        if scheme.is_dark {
+            MaterialDynamicColors::surface_bright()
+        } else {
+            MaterialDynamicColors::surface_dim()
+        }
+    }
+
+    /// All the roles in the catalogue, in the order they are declared.
+    pub fn all_colors() -> Vec<DynamicColor> {
+        vec![
+            MaterialDynamicColors::primary_palette_key_color(),
+            MaterialDynamicColors::secondary_palette_key_color(),
+            MaterialDynamicColors::tertiary_palette_key_color(),
+            MaterialDynamicColors::neutral_palette_key_color(),
+            MaterialDynamicColors::neutral_variant_palette_key_color(),
+            MaterialDynamicColors::background(),
+            MaterialDynamicColors::on_background(),
+            MaterialDynamicColors::surface(),
+            MaterialDynamicColors::surface_dim(),
+            MaterialDynamicColors::surface_bright(),
+            MaterialDynamicColors::surface_container_lowest(),
+            MaterialDynamicColors::surface_container_low(),
+            MaterialDynamicColors::surface_container(),
+            MaterialDynamicColors::surface_container_high(),
+            MaterialDynamicColors::surface_container_highest(),
+            MaterialDynamicColors::on_surface(),
+            MaterialDynamicColors::surface_variant(),
+            MaterialDynamicColors::on_surface_variant(),
+            MaterialDynamicColors::inverse_surface(),
+            MaterialDynamicColors::inverse_on_surface(),
+            MaterialDynamicColors::outline(),
+            MaterialDynamicColors::outline_variant(),
+            MaterialDynamicColors::shadow(),
+            MaterialDynamicColors::scrim(),
+            MaterialDynamicColors::surface_tint(),
+            MaterialDynamicColors::primary(),
+            MaterialDynamicColors::on_primary(),
+            MaterialDynamicColors::primary_container(),
+            MaterialDynamicColors::on_primary_container(),
+            MaterialDynamicColors::inverse_primary(),
+            MaterialDynamicColors::secondary(),
+            MaterialDynamicColors::on_secondary(),
+            MaterialDynamicColors::secondary_container(),
+            MaterialDynamicColors::on_secondary_container(),
+            MaterialDynamicColors::tertiary(),
+            MaterialDynamicColors::on_tertiary(),
+            MaterialDynamicColors::tertiary_container(),
+            MaterialDynamicColors::on_tertiary_container(),
+            MaterialDynamicColors::error(),
+            MaterialDynamicColors::on_error(),
+            MaterialDynamicColors::error_container(),
+            MaterialDynamicColors::on_error_container(),
+            MaterialDynamicColors::primary_fixed(),
+            MaterialDynamicColors::primary_fixed_dim(),
+            MaterialDynamicColors::on_primary_fixed(),
+            MaterialDynamicColors::on_primary_fixed_variant(),
+            MaterialDynamicColors::secondary_fixed(),
+            MaterialDynamicColors::secondary_fixed_dim(),
+            MaterialDynamicColors::on_secondary_fixed(),
+            MaterialDynamicColors::on_secondary_fixed_variant(),
+            MaterialDynamicColors::tertiary_fixed(),
+            MaterialDynamicColors::tertiary_fixed_dim(),
+            MaterialDynamicColors::on_tertiary_fixed(),
+            MaterialDynamicColors::on_tertiary_fixed_variant(),
+        ]
+    }
+
+    pub fn primary_palette_key_color() -> DynamicColor {
+        DynamicColor::from_palette(
+            "primary_palette_key_color",
+            |s| &s.primary_palette,
+            |s| s.primary_palette.key_color().tone(),
+        )
+    }
+
+    pub fn secondary_palette_key_color() -> DynamicColor {
+        DynamicColor::from_palette(
+            "secondary_palette_key_color",
+            |s| &s.secondary_palette,
+            |s| s.secondary_palette.key_color().tone(),
+        )
+    }
+
+    pub fn tertiary_palette_key_color() -> DynamicColor {
+        DynamicColor::from_palette(
+            "tertiary_palette_key_color",
+            |s| &s.tertiary_palette,
+            |s| s.tertiary_palette.key_color().tone(),
+        )
+    }
+
+    pub fn neutral_palette_key_color() -> DynamicColor {
+        DynamicColor::from_palette(
+            "neutral_palette_key_color",
+            |s| &s.neutral_palette,
+            |s| s.neutral_palette.key_color().tone(),
+        )
+    }
+
+    pub fn neutral_variant_palette_key_color() -> DynamicColor {
+        DynamicColor::from_palette(
+            "neutral_variant_palette_key_color",
+            |s| &s.neutral_variant_palette,
+            |s| s.neutral_variant_palette.key_color().tone(),
+        )
+    }
+
+    pub fn background() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "background",
+                |s| &s.neutral_palette,
+                |s| if s.is_dark { 6.0 } else { 98.0 },
+            )
+        }
+    }
+
+    pub fn on_background() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::background()),
+            contrast_curve: Some(ContrastCurve::new(3.0, 3.0, 4.5, 7.0)),
+            ..DynamicColor::from_palette(
+                "on_background",
+                |s| &s.neutral_palette,
+                |s| if s.is_dark { 90.0 } else { 10.0 },
+            )
+        }
+    }
+
+    pub fn surface() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface",
+                |s| &s.neutral_palette,
+                |s| if s.is_dark { 6.0 } else { 98.0 },
+            )
+        }
+    }
+
+    pub fn surface_dim() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_dim",
+                |s| &s.neutral_palette,
+                |s| {
+                    if s.is_dark {
+                        6.0
+                    } else {
+                        ContrastCurve::new(87.0, 87.0, 80.0, 75.0).get(s.contrast_level)
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn surface_bright() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_bright",
+                |s| &s.neutral_palette,
+                |s| {
+                    if s.is_dark {
+                        ContrastCurve::new(24.0, 24.0, 29.0, 34.0).get(s.contrast_level)
+                    } else {
+                        98.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn surface_container_lowest() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_container_lowest",
+                |s| &s.neutral_palette,
+                |s| {
+                    if s.is_dark {
+                        ContrastCurve::new(4.0, 4.0, 2.0, 0.0).get(s.contrast_level)
+                    } else {
+                        100.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn surface_container_low() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_container_low",
+                |s| &s.neutral_palette,
+                |s| {
+                    if s.is_dark {
+                        ContrastCurve::new(10.0, 10.0, 11.0, 12.0).get(s.contrast_level)
+                    } else {
+                        ContrastCurve::new(96.0, 96.0, 96.0, 95.0).get(s.contrast_level)
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn surface_container() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_container",
+                |s| &s.neutral_palette,
+                |s| {
+                    if s.is_dark {
+                        ContrastCurve::new(12.0, 12.0, 16.0, 20.0).get(s.contrast_level)
+                    } else {
+                        ContrastCurve::new(94.0, 94.0, 92.0, 90.0).get(s.contrast_level)
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn surface_container_high() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_container_high",
+                |s| &s.neutral_palette,
+                |s| {
+                    if s.is_dark {
+                        ContrastCurve::new(17.0, 17.0, 21.0, 25.0).get(s.contrast_level)
+                    } else {
+                        ContrastCurve::new(92.0, 92.0, 88.0, 85.0).get(s.contrast_level)
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn surface_container_highest() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_container_highest",
+                |s| &s.neutral_palette,
+                |s| {
+                    if s.is_dark {
+                        ContrastCurve::new(22.0, 22.0, 26.0, 30.0).get(s.contrast_level)
+                    } else {
+                        ContrastCurve::new(90.0, 90.0, 84.0, 80.0).get(s.contrast_level)
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn on_surface() -> DynamicColor {
+        DynamicColor {
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_surface",
+                |s| &s.neutral_palette,
+                |s| if s.is_dark { 90.0 } else { 10.0 },
+            )
+        }
+    }
+
+    pub fn surface_variant() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_variant",
+                |s| &s.neutral_variant_palette,
+                |s| if s.is_dark { 30.0 } else { 90.0 },
+            )
+        }
+    }
+
+    pub fn on_surface_variant() -> DynamicColor {
+        DynamicColor {
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 11.0)),
+            ..DynamicColor::from_palette(
+                "on_surface_variant",
+                |s| &s.neutral_variant_palette,
+                |s| if s.is_dark { 80.0 } else { 30.0 },
+            )
+        }
+    }
+
+    pub fn inverse_surface() -> DynamicColor {
+        DynamicColor::from_palette(
+            "inverse_surface",
+            |s| &s.neutral_palette,
+            |s| if s.is_dark { 90.0 } else { 20.0 },
+        )
+    }
+
+    pub fn inverse_on_surface() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::inverse_surface()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "inverse_on_surface",
+                |s| &s.neutral_palette,
+                |s| if s.is_dark { 20.0 } else { 95.0 },
+            )
+        }
+    }
+
+    pub fn outline() -> DynamicColor {
+        DynamicColor {
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.5, 3.0, 4.5, 7.0)),
+            ..DynamicColor::from_palette(
+                "outline",
+                |s| &s.neutral_variant_palette,
+                |s| if s.is_dark { 60.0 } else { 50.0 },
+            )
+        }
+    }
+
+    pub fn outline_variant() -> DynamicColor {
+        DynamicColor {
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            ..DynamicColor::from_palette(
+                "outline_variant",
+                |s| &s.neutral_variant_palette,
+                |s| if s.is_dark { 30.0 } else { 80.0 },
+            )
+        }
+    }
+
+    pub fn shadow() -> DynamicColor {
+        DynamicColor::from_palette("shadow", |s| &s.neutral_palette, |_| 0.0)
+    }
+
+    pub fn scrim() -> DynamicColor {
+        DynamicColor::from_palette("scrim", |s| &s.neutral_palette, |_| 0.0)
+    }
+
+    pub fn surface_tint() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            ..DynamicColor::from_palette(
+                "surface_tint",
+                |s| &s.primary_palette,
+                |s| if s.is_dark { 80.0 } else { 40.0 },
+            )
+        }
+    }
+
+    pub fn primary() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 7.0)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::primary_container(),
+                    MaterialDynamicColors::primary(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "primary",
+                |s| &s.primary_palette,
+                |s| {
+                    if is_monochrome(s) {
+                        if s.is_dark {
+                            100.0
+                        } else {
+                            0.0
+                        }
+                    } else if s.is_dark {
+                        80.0
+                    } else {
+                        40.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn on_primary() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::primary()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_primary",
+                |s| &s.primary_palette,
+                |s| {
+                    if is_monochrome(s) {
+                        if s.is_dark {
+                            10.0
+                        } else {
+                            90.0
+                        }
+                    } else if s.is_dark {
+                        20.0
+                    } else {
+                        100.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn primary_container() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::primary_container(),
+                    MaterialDynamicColors::primary(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "primary_container",
+                |s| &s.primary_palette,
+                |s| {
+                    if is_fidelity(s) {
+                        s.source_color_hct.tone()
+                    } else if is_monochrome(s) {
+                        if s.is_dark {
+                            85.0
+                        } else {
+                            25.0
+                        }
+                    } else if s.is_dark {
+                        30.0
+                    } else {
+                        90.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn on_primary_container() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::primary_container()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_primary_container",
+                |s| &s.primary_palette,
+                |s| {
+                    if is_fidelity(s) {
+                        DynamicColor::foreground_tone(
+                            (MaterialDynamicColors::primary_container().tone)(s),
+                            4.5,
+                        )
+                    } else if is_monochrome(s) {
+                        if s.is_dark {
+                            0.0
+                        } else {
+                            100.0
+                        }
+                    } else if s.is_dark {
+                        90.0
+                    } else {
+                        10.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn inverse_primary() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::inverse_surface()),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 7.0)),
+            ..DynamicColor::from_palette(
+                "inverse_primary",
+                |s| &s.primary_palette,
+                |s| if s.is_dark { 40.0 } else { 80.0 },
+            )
+        }
+    }
+
+    pub fn secondary() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 7.0)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::secondary_container(),
+                    MaterialDynamicColors::secondary(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "secondary",
+                |s| &s.secondary_palette,
+                |s| if s.is_dark { 80.0 } else { 40.0 },
+            )
+        }
+    }
+
+    pub fn on_secondary() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::secondary()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_secondary",
+                |s| &s.secondary_palette,
+                |s| {
+                    if is_monochrome(s) {
+                        if s.is_dark {
+                            10.0
+                        } else {
+                            100.0
+                        }
+                    } else if s.is_dark {
+                        20.0
+                    } else {
+                        100.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn secondary_container() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::secondary_container(),
+                    MaterialDynamicColors::secondary(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "secondary_container",
+                |s| &s.secondary_palette,
+                |s| {
+                    let initial_tone = if s.is_dark { 30.0 } else { 90.0 };
+                    if is_monochrome(s) {
+                        return if s.is_dark { 30.0 } else { 85.0 };
+                    }
+                    if !is_fidelity(s) {
+                        return initial_tone;
+                    }
+                    find_desired_chroma_by_tone(&s.secondary_palette, initial_tone, !s.is_dark)
+                },
+            )
+        }
+    }
+
+    pub fn on_secondary_container() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::secondary_container()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_secondary_container",
+                |s| &s.secondary_palette,
+                |s| {
+                    if !is_fidelity(s) {
+                        return if s.is_dark { 90.0 } else { 10.0 };
+                    }
+                    DynamicColor::foreground_tone(
+                        (MaterialDynamicColors::secondary_container().tone)(s),
+                        4.5,
+                    )
+                },
+            )
+        }
+    }
+
+    pub fn tertiary() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 7.0)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::tertiary_container(),
+                    MaterialDynamicColors::tertiary(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "tertiary",
+                |s| &s.tertiary_palette,
+                |s| {
+                    if is_monochrome(s) {
+                        if s.is_dark {
+                            90.0
+                        } else {
+                            25.0
+                        }
+                    } else if s.is_dark {
+                        80.0
+                    } else {
+                        40.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn on_tertiary() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::tertiary()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_tertiary",
+                |s| &s.tertiary_palette,
+                |s| {
+                    if is_monochrome(s) {
+                        if s.is_dark {
+                            10.0
+                        } else {
+                            90.0
+                        }
+                    } else if s.is_dark {
+                        20.0
+                    } else {
+                        100.0
+                    }
+                },
+            )
+        }
+    }
+
+    pub fn tertiary_container() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::tertiary_container(),
+                    MaterialDynamicColors::tertiary(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "tertiary_container",
+                |s| &s.tertiary_palette,
+                |s| {
+                    if is_monochrome(s) {
+                        return if s.is_dark { 60.0 } else { 49.0 };
+                    }
+                    if !is_fidelity(s) {
+                        return if s.is_dark { 30.0 } else { 90.0 };
+                    }
+                    let proposed_hct = s.tertiary_palette.get_hct(s.source_color_hct.tone());
+                    DislikeAnalyzer::fix_if_disliked(proposed_hct).tone()
+                },
+            )
+        }
+    }
+
+    pub fn on_tertiary_container() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::tertiary_container()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_tertiary_container",
+                |s| &s.tertiary_palette,
+                |s| {
+                    if is_monochrome(s) {
+                        return if s.is_dark { 0.0 } else { 100.0 };
+                    }
+                    if !is_fidelity(s) {
+                        return if s.is_dark { 90.0 } else { 10.0 };
+                    }
+                    DynamicColor::foreground_tone(
+                        (MaterialDynamicColors::tertiary_container().tone)(s),
+                        4.5,
+                    )
+                },
+            )
+        }
+    }
+
+    pub fn error() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 7.0)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::error_container(),
+                    MaterialDynamicColors::error(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "error",
+                |s| &s.error_palette,
+                |s| if s.is_dark { 80.0 } else { 40.0 },
+            )
+        }
+    }
+
+    pub fn on_error() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::error()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_error",
+                |s| &s.error_palette,
+                |s| if s.is_dark { 20.0 } else { 100.0 },
+            )
+        }
+    }
+
+    pub fn error_container() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::error_container(),
+                    MaterialDynamicColors::error(),
+                    10.0,
+                    TonePolarity::Nearer,
+                    false,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "error_container",
+                |s| &s.error_palette,
+                |s| if s.is_dark { 30.0 } else { 90.0 },
+            )
+        }
+    }
+
+    pub fn on_error_container() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::error_container()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_error_container",
+                |s| &s.error_palette,
+                |s| if s.is_dark { 90.0 } else { 10.0 },
+            )
+        }
+    }
+
+    pub fn primary_fixed() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::primary_fixed(),
+                    MaterialDynamicColors::primary_fixed_dim(),
+                    10.0,
+                    TonePolarity::Lighter,
+                    true,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "primary_fixed",
+                |s| &s.primary_palette,
+                |s| if is_monochrome(s) { 40.0 } else { 90.0 },
+            )
+        }
+    }
+
+    pub fn primary_fixed_dim() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::primary_fixed(),
+                    MaterialDynamicColors::primary_fixed_dim(),
+                    10.0,
+                    TonePolarity::Lighter,
+                    true,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "primary_fixed_dim",
+                |s| &s.primary_palette,
+                |s| if is_monochrome(s) { 30.0 } else { 80.0 },
+            )
+        }
+    }
+
+    pub fn on_primary_fixed() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::primary_fixed_dim()),
+            second_background: Some(|_| MaterialDynamicColors::primary_fixed()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_primary_fixed",
+                |s| &s.primary_palette,
+                |s| if is_monochrome(s) { 100.0 } else { 10.0 },
+            )
+        }
+    }
+
+    pub fn on_primary_fixed_variant() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::primary_fixed_dim()),
+            second_background: Some(|_| MaterialDynamicColors::primary_fixed()),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 11.0)),
+            ..DynamicColor::from_palette(
+                "on_primary_fixed_variant",
+                |s| &s.primary_palette,
+                |s| if is_monochrome(s) { 90.0 } else { 30.0 },
+            )
+        }
+    }
+
+    pub fn secondary_fixed() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::secondary_fixed(),
+                    MaterialDynamicColors::secondary_fixed_dim(),
+                    10.0,
+                    TonePolarity::Lighter,
+                    true,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "secondary_fixed",
+                |s| &s.secondary_palette,
+                |s| if is_monochrome(s) { 80.0 } else { 90.0 },
+            )
+        }
+    }
+
+    pub fn secondary_fixed_dim() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::secondary_fixed(),
+                    MaterialDynamicColors::secondary_fixed_dim(),
+                    10.0,
+                    TonePolarity::Lighter,
+                    true,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "secondary_fixed_dim",
+                |s| &s.secondary_palette,
+                |s| if is_monochrome(s) { 70.0 } else { 80.0 },
+            )
+        }
+    }
+
+    pub fn on_secondary_fixed() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::secondary_fixed_dim()),
+            second_background: Some(|_| MaterialDynamicColors::secondary_fixed()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette("on_secondary_fixed", |s| &s.secondary_palette, |_| 10.0)
+        }
+    }
+
+    pub fn on_secondary_fixed_variant() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::secondary_fixed_dim()),
+            second_background: Some(|_| MaterialDynamicColors::secondary_fixed()),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 11.0)),
+            ..DynamicColor::from_palette(
+                "on_secondary_fixed_variant",
+                |s| &s.secondary_palette,
+                |s| if is_monochrome(s) { 25.0 } else { 30.0 },
+            )
+        }
+    }
+
+    pub fn tertiary_fixed() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::tertiary_fixed(),
+                    MaterialDynamicColors::tertiary_fixed_dim(),
+                    10.0,
+                    TonePolarity::Lighter,
+                    true,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "tertiary_fixed",
+                |s| &s.tertiary_palette,
+                |s| if is_monochrome(s) { 40.0 } else { 90.0 },
+            )
+        }
+    }
+
+    pub fn tertiary_fixed_dim() -> DynamicColor {
+        DynamicColor {
+            is_background: true,
+            background: Some(MaterialDynamicColors::highest_surface),
+            contrast_curve: Some(ContrastCurve::new(1.0, 1.0, 3.0, 4.5)),
+            tone_delta_pair: Some(|_| {
+                ToneDeltaPair::new(
+                    MaterialDynamicColors::tertiary_fixed(),
+                    MaterialDynamicColors::tertiary_fixed_dim(),
+                    10.0,
+                    TonePolarity::Lighter,
+                    true,
+                )
+            }),
+            ..DynamicColor::from_palette(
+                "tertiary_fixed_dim",
+                |s| &s.tertiary_palette,
+                |s| if is_monochrome(s) { 30.0 } else { 80.0 },
+            )
+        }
+    }
+
+    pub fn on_tertiary_fixed() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::tertiary_fixed_dim()),
+            second_background: Some(|_| MaterialDynamicColors::tertiary_fixed()),
+            contrast_curve: Some(ContrastCurve::new(4.5, 7.0, 11.0, 21.0)),
+            ..DynamicColor::from_palette(
+                "on_tertiary_fixed",
+                |s| &s.tertiary_palette,
+                |s| if is_monochrome(s) { 100.0 } else { 10.0 },
+            )
+        }
+    }
+
+    pub fn on_tertiary_fixed_variant() -> DynamicColor {
+        DynamicColor {
+            background: Some(|_| MaterialDynamicColors::tertiary_fixed_dim()),
+            second_background: Some(|_| MaterialDynamicColors::tertiary_fixed()),
+            contrast_curve: Some(ContrastCurve::new(3.0, 4.5, 7.0, 11.0)),
+            ..DynamicColor::from_palette(
+                "on_tertiary_fixed_variant",
+                |s| &s.tertiary_palette,
+                |s| if is_monochrome(s) { 90.0 } else { 30.0 },
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dynamiccolor::{MaterialDynamicColors, Variant},
+        hct::Hct,
+        palette::TonalPalette,
+        scheme::DynamicScheme,
+    };
+
+    fn scheme(source: u32, variant: Variant, is_dark: bool) -> DynamicScheme {
+        DynamicScheme::from_variant(Hct::from_int(source), variant, is_dark, 0.0)
+    }
+
+    #[test]
+    fn standard_light_tones() {
+        let scheme = scheme(0xff0000ff, Variant::TonalSpot, false);
+
+        assert_eq!(MaterialDynamicColors::primary().get_tone(&scheme), 40.0);
+        assert_eq!(MaterialDynamicColors::on_primary().get_tone(&scheme), 100.0);
+        assert_eq!(
+            MaterialDynamicColors::primary_container().get_tone(&scheme),
+            90.0
+        );
+        assert_eq!(
+            MaterialDynamicColors::on_primary_container().get_tone(&scheme),
+            10.0
+        );
+        assert_eq!(MaterialDynamicColors::surface().get_tone(&scheme), 98.0);
+        assert_eq!(MaterialDynamicColors::surface_dim().get_tone(&scheme), 87.0);
+        assert_eq!(MaterialDynamicColors::on_surface().get_tone(&scheme), 10.0);
+        assert_eq!(
+            MaterialDynamicColors::surface_container_lowest().get_tone(&scheme),
+            100.0
+        );
+    }
+
+    #[test]
+    fn standard_dark_tones() {
+        let scheme = scheme(0xff0000ff, Variant::TonalSpot, true);
+
+        assert_eq!(MaterialDynamicColors::primary().get_tone(&scheme), 80.0);
+        assert_eq!(MaterialDynamicColors::on_primary().get_tone(&scheme), 20.0);
+        assert_eq!(
+            MaterialDynamicColors::primary_container().get_tone(&scheme),
+            30.0
+        );
+        assert_eq!(MaterialDynamicColors::surface().get_tone(&scheme), 6.0);
+        assert_eq!(
+            MaterialDynamicColors::surface_bright().get_tone(&scheme),
+            24.0
+        );
+        assert_eq!(MaterialDynamicColors::on_surface().get_tone(&scheme), 90.0);
+    }
+
+    #[test]
+    fn monochrome_primary_is_black_or_white() {
+        let light = scheme(0xff0000ff, Variant::Monochrome, false);
+        let dark = scheme(0xff0000ff, Variant::Monochrome, true);
+
+        assert_eq!(MaterialDynamicColors::primary().get_tone(&light), 0.0);
+        assert_eq!(MaterialDynamicColors::primary().get_tone(&dark), 100.0);
+    }
+
+    #[test]
+    fn fidelity_primary_container_matches_source_tone() {
+        let scheme = scheme(0xff0000ff, Variant::Fidelity, false);
+        let source_tone = Hct::from_int(0xff0000ff).tone();
+
+        assert_eq!(
+            MaterialDynamicColors::primary_container().get_tone(&scheme),
+            source_tone
+        );
+    }
+
+    #[test]
+    fn fidelity_tertiary_container_avoids_disliked_colors() {
+        let source = Hct::new(40.0, 48.0, 40.0);
+        let scheme = DynamicScheme::new(
+            source,
+            Variant::Fidelity,
+            false,
+            0.0,
+            TonalPalette::of(source.hue(), source.chroma()),
+            TonalPalette::of(source.hue(), 16.0),
+            TonalPalette::of(100.0, 40.0),
+            TonalPalette::of(source.hue(), 6.0),
+            TonalPalette::of(source.hue(), 10.0),
+        );
+
+        let tone = (MaterialDynamicColors::tertiary_container().tone)(&scheme);
+        assert!((tone - 70.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn role_names_are_unique() {
+        let colors = MaterialDynamicColors::all_colors();
+        let mut names: Vec<&str> = colors.iter().map(|color| color.name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+
+        assert_eq!(names.len(), colors.len());
+    }
+}
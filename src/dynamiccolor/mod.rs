@@ -0,0 +1,13 @@
+pub mod contrast_curve;
+pub mod dynamic_color;
+pub mod material_dynamic_colors;
+pub mod tone_delta_pair;
+pub mod variant;
+
+pub use self::{
+    contrast_curve::ContrastCurve,
+    dynamic_color::DynamicColor,
+    material_dynamic_colors::MaterialDynamicColors,
+    tone_delta_pair::{ToneDeltaPair, TonePolarity},
+    variant::Variant,
+};
@@ -0,0 +1,57 @@
+use super::DynamicColor;
+
+/// Describes the relationship in lightness between two colors.
+///
+/// `Nearer` and `Farther` describes closeness to the surface roles. For
+/// instance, `ToneDeltaPair::new(a, b, 10.0, TonePolarity::Nearer, stay_together)`
+/// states that `a` should be 10 lighter than `b` in light mode, and 10 darker
+/// than `b` in dark mode.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TonePolarity {
+    Darker,
+    Lighter,
+    Nearer,
+    Farther,
+}
+
+/// Documents a constraint between two [`DynamicColor`]s, in which their tones
+/// must have a certain distance from each other.
+///
+/// Prefer a [`DynamicColor`] with a background; this is for special cases when
+/// designers want tonal distance, literally contrast, between two colors that
+/// don't have a background / foreground relationship or a contrast guarantee.
+#[derive(Debug, Clone)]
+pub struct ToneDeltaPair {
+    /// The first role in a pair.
+    pub role_a: DynamicColor,
+    /// The second role in a pair.
+    pub role_b: DynamicColor,
+    /// Required difference between tones. Absolute value, negative values have
+    /// undefined behavior.
+    pub delta: f64,
+    /// The relative relation between tones of `role_a` and `role_b`, as
+    /// described above.
+    pub polarity: TonePolarity,
+    /// Whether these two roles should stay on the same side of the "awkward
+    /// zone" (T50-59). This is necessary for certain cases where one role has
+    /// two backgrounds.
+    pub stay_together: bool,
+}
+
+impl ToneDeltaPair {
+    pub fn new(
+        role_a: DynamicColor,
+        role_b: DynamicColor,
+        delta: f64,
+        polarity: TonePolarity,
+        stay_together: bool,
+    ) -> ToneDeltaPair {
+        ToneDeltaPair {
+            role_a,
+            role_b,
+            delta,
+            polarity,
+            stay_together,
+        }
+    }
+}
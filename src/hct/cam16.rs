@@ -44,7 +44,7 @@ impl Cam16 {
     }
 
     pub fn from_int(argb: u32) -> Cam16 {
-        Cam16::from_int_in_viewing_conditions(argb, &ViewingConditions::default())
+        Cam16::from_int_in_viewing_conditions(argb, ViewingConditions::default_ref())
     }
 
     pub fn from_int_in_viewing_conditions(
@@ -53,10 +53,17 @@ impl Cam16 {
     ) -> Cam16 {
         // Transform ARGB int to XYZ
         let xyz = xyz_from_argb(argb);
-        let x = xyz[0];
-        let y = xyz[1];
-        let z = xyz[2];
+        Cam16::from_xyz_in_viewing_conditions(xyz[0], xyz[1], xyz[2], viewing_conditions)
+    }
 
+    /// Create a CAM16 color from XYZ coordinates `x`, `y` and `z`, assuming
+    /// the color was viewed in `viewing_conditions`.
+    pub fn from_xyz_in_viewing_conditions(
+        x: f64,
+        y: f64,
+        z: f64,
+        viewing_conditions: &ViewingConditions,
+    ) -> Cam16 {
         // Transform XYZ to 'cone'/'rgb' responses
 
         let r_c = 0.401288 * x + 0.650173 * y - 0.051461 * z;
@@ -152,7 +159,7 @@ impl Cam16 {
     /// Create a CAM16 color from lightness `j`, chroma `c`, and hue `h`,
     /// assuming the color was viewed in default viewing conditions.
     pub fn from_jch(j: f64, c: f64, h: f64) -> Cam16 {
-        Cam16::from_jch_in_viewing_conditions(j, c, h, &ViewingConditions::default())
+        Cam16::from_jch_in_viewing_conditions(j, c, h, ViewingConditions::default_ref())
     }
 
     pub fn from_jch_in_viewing_conditions(
@@ -194,7 +201,7 @@ impl Cam16 {
     /// Create a CAM16 color from CAM16-UCS coordinates `jstar`, `astar`, `bstar`
     /// assuming the color was viewed in default viewing conditions.
     pub fn from_ucs(jstar: f64, astar: f64, bstar: f64) -> Cam16 {
-        Cam16::from_ucs_in_viewing_conditions(jstar, astar, bstar, &ViewingConditions::default())
+        Cam16::from_ucs_in_viewing_conditions(jstar, astar, bstar, ViewingConditions::default_ref())
     }
 
     /// Create a CAM16 color from CAM16-UCS coordinates `jstar`, `astar`, `bstar`
@@ -220,12 +227,20 @@ impl Cam16 {
     }
 
     pub fn viewed_in_srgb(&self) -> u32 {
-        self.viewed(&ViewingConditions::default())
+        self.viewed(ViewingConditions::default_ref())
     }
 
     /// ARGB representation of a color, given the color was viewed in
     /// [`ViewingConditions`]
     pub fn viewed(&self, viewing_conditions: &ViewingConditions) -> u32 {
+        let xyz = self.xyz_in_viewing_conditions(viewing_conditions);
+        argb_from_xyz(xyz[0], xyz[1], xyz[2])
+    }
+
+    /// XYZ coordinates of a color, given the color was viewed in
+    /// [`ViewingConditions`]. Unlike [`Cam16::viewed`], the result is not
+    /// clamped to the sRGB gamut.
+    pub fn xyz_in_viewing_conditions(&self, viewing_conditions: &ViewingConditions) -> [f64; 3] {
         let alpha = if self.chroma == 0.0 || self.j == 0.0 {
             0.0
         } else {
@@ -273,6 +288,6 @@ impl Cam16 {
         let y = 0.38752654 * r_f + 0.62144744 * g_f - 0.00897398 * b_f;
         let z = -0.01584150 * r_f - 0.03412294 * g_f + 1.04996444 * b_f;
 
-        argb_from_xyz(x, y, z)
+        [x, y, z]
     }
 }
@@ -29,6 +29,37 @@ const LINRGB_FROM_SCALED_DISCOUNT: [[f64; 3]; 3] = [
 
 const Y_FROM_LINRGB: [f64; 3] = [0.2126, 0.7152, 0.0722];
 
+/// Controls when [`find_result_by_j`] accepts a result.
+struct Convergence {
+    /// How far below 0 a linear RGB component may be and still be in gamut.
+    linrgb_tolerance: f64,
+    /// How close to the requested Y the result must be.
+    y_tolerance: f64,
+    /// The maximum number of Newton iterations.
+    rounds: usize,
+    /// Whether to give up as soon as an iteration leaves the gamut, rather
+    /// than only checking the accepted result.
+    exit_early: bool,
+}
+
+const DEFAULT_CONVERGENCE: Convergence = Convergence {
+    linrgb_tolerance: 0.0,
+    y_tolerance: 0.002,
+    rounds: 5,
+    exit_early: true,
+};
+
+// Without a precomputed gamut boundary to fall back on, colors on the edge of
+// the sRGB cube are accepted when they fall slightly outside of it due to
+// rounding in the computed conversion matrix, and the initial estimate of J,
+// which is tuned for the default viewing conditions, is refined further.
+const CUSTOM_VIEWING_CONDITIONS_CONVERGENCE: Convergence = Convergence {
+    linrgb_tolerance: 0.01,
+    y_tolerance: 0.00001,
+    rounds: 20,
+    exit_early: false,
+};
+
 const CRITICAL_PLANES: [f64; 255] = [
     0.015176349177441876,
     0.045529047532325624,
@@ -362,7 +393,7 @@ fn set_coordinate(source: [f64; 3], coordinate: f64, target: [f64; 3], axis: usi
 }
 
 fn is_bounded(x: f64) -> bool {
-    0.0 <= x && x <= 100.0
+    (0.0..=100.0).contains(&x)
 }
 
 /// Given a plane Y = [y] and an zero-based index [n] such that 0
@@ -376,27 +407,27 @@ fn nth_vertex(y: f64, n: u32) -> [f64; 3] {
     let k_g = Y_FROM_LINRGB[1];
     let k_b = Y_FROM_LINRGB[2];
     let coord_a = if n % 4 <= 1 { 0.0 } else { 100.0 };
-    let coord_b = if n % 2 == 0 { 0.0 } else { 100.0 };
+    let coord_b = if n.is_multiple_of(2) { 0.0 } else { 100.0 };
     if n < 4 {
         let r = (y - coord_a * k_g - coord_b * k_b) / k_r;
         if is_bounded(r) {
-            return [r, coord_a, coord_b];
+            [r, coord_a, coord_b]
         } else {
-            return [-1.0, -1.0, -1.0];
+            [-1.0, -1.0, -1.0]
         }
     } else if n < 8 {
         let g = (y - coord_b * k_r - coord_a * k_b) / k_g;
         if is_bounded(g) {
-            return [coord_b, g, coord_a];
+            [coord_b, g, coord_a]
         } else {
-            return [-1.0, -1.0, -1.0];
+            [-1.0, -1.0, -1.0]
         }
     } else {
         let b = (y - coord_a * k_r - coord_b * k_g) / k_b;
         if is_bounded(b) {
-            return [coord_a, coord_b, b];
+            [coord_a, coord_b, b]
         } else {
-            return [-1.0, -1.0, -1.0];
+            [-1.0, -1.0, -1.0]
         }
     }
 }
@@ -508,24 +539,82 @@ fn inverse_chromatic_adaptation(adapted: f64) -> f64 {
     math_utils::signum(adapted) as f64 * base.powf(1.0 / 0.42)
 }
 
+/// Returns the matrix that converts scaled discounted cone responses, as
+/// produced by [`inverse_chromatic_adaptation`], into linear RGB under
+/// `viewing_conditions`.
+///
+/// For the default viewing conditions this is [`LINRGB_FROM_SCALED_DISCOUNT`].
+fn linrgb_from_scaled_discount(viewing_conditions: &ViewingConditions) -> [[f64; 3]; 3] {
+    // Cone responses to XYZ, as in Cam16::from_xyz_in_viewing_conditions.
+    let cone_from_xyz = [
+        [0.401288, 0.650173, -0.051461],
+        [-0.250268, 1.204414, 0.045854],
+        [-0.002079, 0.048952, 0.953127],
+    ];
+    // Build the forward transform, linear RGB to scaled discounted cone
+    // responses, then invert it.
+    let mut scaled_discount_from_linrgb = [[0.0; 3]; 3];
+    for (row, matrix_row) in scaled_discount_from_linrgb.iter_mut().enumerate() {
+        let scale = viewing_conditions.fl * viewing_conditions.rgb_d[row] / 100.0;
+        for (column, entry) in matrix_row.iter_mut().enumerate() {
+            *entry = (0..3)
+                .map(|k| cone_from_xyz[row][k] * color_utils::SRGB_TO_XYZ[k][column])
+                .sum::<f64>()
+                * scale;
+        }
+    }
+    invert_matrix(scaled_discount_from_linrgb)
+}
+
+fn invert_matrix(m: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let cofactor =
+        |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let adjugate = [
+        [
+            cofactor(1, 2, 1, 2),
+            -cofactor(0, 2, 1, 2),
+            cofactor(0, 1, 1, 2),
+        ],
+        [
+            -cofactor(1, 2, 0, 2),
+            cofactor(0, 2, 0, 2),
+            -cofactor(0, 1, 0, 2),
+        ],
+        [
+            cofactor(1, 2, 0, 1),
+            -cofactor(0, 2, 0, 1),
+            cofactor(0, 1, 0, 1),
+        ],
+    ];
+    let determinant =
+        m[0][0] * adjugate[0][0] + m[0][1] * adjugate[1][0] + m[0][2] * adjugate[2][0];
+    adjugate.map(|row| row.map(|entry| entry / determinant))
+}
+
 /// Finds a color with the given hue, chroma, and Y.
 ///
 /// Returns a color with the desired [hue_radians], [chroma], and [y]
 /// as a hexadecimal integer, if found; and returns 0 otherwise.
-fn find_result_by_j(hue_radians: f64, chroma: f64, y: f64) -> u32 {
+fn find_result_by_j(
+    hue_radians: f64,
+    chroma: f64,
+    y: f64,
+    viewing_conditions: &ViewingConditions,
+    linrgb_from_scaled_discount: [[f64; 3]; 3],
+    convergence: &Convergence,
+) -> u32 {
     // Initial estimate of j.
     let mut j = y.sqrt() * 11.0;
     // ===========================================================
     // Operations inlined from Cam16 to avoid repeated calculation
     // ===========================================================
-    let viewing_conditions = ViewingConditions::default();
     let t_inner_coeff =
         1.0 / (1.64 - 0.29f64.powf(viewing_conditions.background_y_to_white_point_y)).powf(0.73);
     let e_hue = 0.25 * ((hue_radians + 2.0).cos() + 3.8);
     let p1 = e_hue * (50000.0 / 13.0) * viewing_conditions.nc * viewing_conditions.ncb;
     let h_sin = hue_radians.sin();
     let h_cos = hue_radians.cos();
-    for iteration_round in 0..5 {
+    for iteration_round in 0..convergence.rounds {
         // ===========================================================
         // Operations inlined from Cam16 to avoid repeated calculation
         // ===========================================================
@@ -550,12 +639,15 @@ fn find_result_by_j(hue_radians: f64, chroma: f64, y: f64) -> u32 {
         let b_c_scaled = inverse_chromatic_adaptation(b_a);
         let linrgb = math_utils::matrix_multiply(
             [r_c_scaled, g_c_scaled, b_c_scaled],
-            LINRGB_FROM_SCALED_DISCOUNT,
+            linrgb_from_scaled_discount,
         );
         // ===========================================================
         // Operations inlined from Cam16 to avoid repeated calculation
         // ===========================================================
-        if linrgb[0] < 0.0 || linrgb[1] < 0.0 || linrgb[2] < 0.0 {
+        let below_gamut = linrgb[0] < -convergence.linrgb_tolerance
+            || linrgb[1] < -convergence.linrgb_tolerance
+            || linrgb[2] < -convergence.linrgb_tolerance;
+        if below_gamut && convergence.exit_early {
             return 0;
         }
         let k_r = Y_FROM_LINRGB[0];
@@ -565,8 +657,8 @@ fn find_result_by_j(hue_radians: f64, chroma: f64, y: f64) -> u32 {
         if fnj <= 0.0 {
             return 0;
         }
-        if iteration_round == 4 || (fnj - y).abs() < 0.002 {
-            if linrgb[0] > 100.01 || linrgb[1] > 100.01 || linrgb[2] > 100.01 {
+        if iteration_round == convergence.rounds - 1 || (fnj - y).abs() < convergence.y_tolerance {
+            if below_gamut || linrgb[0] > 100.01 || linrgb[1] > 100.01 || linrgb[2] > 100.01 {
                 return 0;
             }
             return color_utils::argb_from_linrgb(linrgb);
@@ -587,13 +679,20 @@ fn find_result_by_j(hue_radians: f64, chroma: f64, y: f64) -> u32 {
 /// the hue and L* will be sufficiently close,
 /// and the chroma will be maximized.
 pub fn solve_to_int(hue_degrees: f64, chroma: f64, lstar: f64) -> u32 {
-    if chroma < 0.0001 || lstar < 0.0001 || lstar > 99.9999 {
+    if chroma < 0.0001 || !(0.0001..=99.9999).contains(&lstar) {
         return color_utils::argb_from_lstar(lstar);
     }
     let hue_degrees = math_utils::sanitize_degrees_double(hue_degrees);
     let hue_radians = hue_degrees / 180.0 * std::f64::consts::PI;
     let y = color_utils::y_from_lstar(lstar);
-    let exact_answer = find_result_by_j(hue_radians, chroma, y);
+    let exact_answer = find_result_by_j(
+        hue_radians,
+        chroma,
+        y,
+        ViewingConditions::default_ref(),
+        LINRGB_FROM_SCALED_DISCOUNT,
+        &DEFAULT_CONVERGENCE,
+    );
     if exact_answer != 0 {
         return exact_answer;
     }
@@ -601,6 +700,68 @@ pub fn solve_to_int(hue_degrees: f64, chroma: f64, lstar: f64) -> u32 {
     color_utils::argb_from_linrgb(linrgb)
 }
 
+/// Finds an sRGB color with the given hue, chroma, and L*, where hue and
+/// chroma are measured in `viewing_conditions`, if possible.
+///
+/// Returns a hexadecimal representing a sRGB color with
+/// its hue, chroma, and L* sufficiently close to
+/// [hue_degrees], [chroma], and [lstar], respectively.
+/// If it is impossible to satisfy all three constraints,
+/// the hue and L* will be sufficiently close,
+/// and the chroma will be maximized.
+pub fn solve_to_int_in_viewing_conditions(
+    hue_degrees: f64,
+    chroma: f64,
+    lstar: f64,
+    viewing_conditions: &ViewingConditions,
+) -> u32 {
+    if viewing_conditions == ViewingConditions::default_ref() {
+        return solve_to_int(hue_degrees, chroma, lstar);
+    }
+    if chroma < 0.0001 || !(0.0001..=99.9999).contains(&lstar) {
+        return color_utils::argb_from_lstar(lstar);
+    }
+    let hue_degrees = math_utils::sanitize_degrees_double(hue_degrees);
+    let hue_radians = hue_degrees / 180.0 * std::f64::consts::PI;
+    let y = color_utils::y_from_lstar(lstar);
+    let linrgb_from_scaled_discount = linrgb_from_scaled_discount(viewing_conditions);
+    let find = |chroma: f64| {
+        find_result_by_j(
+            hue_radians,
+            chroma,
+            y,
+            viewing_conditions,
+            linrgb_from_scaled_discount,
+            &CUSTOM_VIEWING_CONDITIONS_CONVERGENCE,
+        )
+    };
+    let exact_answer = find(chroma);
+    if exact_answer != 0 {
+        return exact_answer;
+    }
+    // The gamut boundary precomputed for bisect_to_limit only holds for the
+    // default viewing conditions, so search for the most chromatic color
+    // with the requested hue and L* instead.
+    let mut low = 0.0;
+    let mut high = chroma;
+    let mut answer = find(low);
+    for _ in 0..20 {
+        let mid = (low + high) / 2.0;
+        let candidate = find(mid);
+        if candidate != 0 {
+            low = mid;
+            answer = candidate;
+        } else {
+            high = mid;
+        }
+    }
+    if answer != 0 {
+        answer
+    } else {
+        color_utils::argb_from_lstar(lstar)
+    }
+}
+
 /// Finds a CAM16 object with the given hue, chroma, and L*, if possible.
 ///
 /// Returns a CAM16 object representing a sRGB color with
@@ -612,3 +773,35 @@ pub fn solve_to_int(hue_degrees: f64, chroma: f64, lstar: f64) -> u32 {
 pub fn solve_to_cam(hue_degrees: f64, chroma: f64, lstar: f64) -> Cam16 {
     Cam16::from_int(solve_to_int(hue_degrees, chroma, lstar))
 }
+
+/// Finds a CAM16 object with the given hue, chroma, and L*, where hue and
+/// chroma are measured in `viewing_conditions`, if possible.
+///
+/// See [`solve_to_int_in_viewing_conditions`].
+pub fn solve_to_cam_in_viewing_conditions(
+    hue_degrees: f64,
+    chroma: f64,
+    lstar: f64,
+    viewing_conditions: &ViewingConditions,
+) -> Cam16 {
+    Cam16::from_int_in_viewing_conditions(
+        solve_to_int_in_viewing_conditions(hue_degrees, chroma, lstar, viewing_conditions),
+        viewing_conditions,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{linrgb_from_scaled_discount, LINRGB_FROM_SCALED_DISCOUNT};
+    use crate::hct::ViewingConditions;
+
+    #[test]
+    fn default_linrgb_from_scaled_discount_matches_constant() {
+        let matrix = linrgb_from_scaled_discount(&ViewingConditions::default());
+        for (row, expected_row) in matrix.iter().zip(LINRGB_FROM_SCALED_DISCOUNT) {
+            for (entry, expected) in row.iter().zip(expected_row) {
+                assert!((entry - expected).abs() < 1e-6 * expected.abs().max(1.0));
+            }
+        }
+    }
+}
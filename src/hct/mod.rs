@@ -2,18 +2,28 @@ pub mod cam16;
 pub mod hct_solver;
 pub mod viewing_conditions;
 
-pub use self::{cam16::Cam16, hct_solver::solve_to_int, viewing_conditions::ViewingConditions};
-use crate::utils::color_utils::lstar_from_argb;
+pub use self::{
+    cam16::Cam16,
+    hct_solver::{solve_to_int, solve_to_int_in_viewing_conditions},
+    viewing_conditions::{white_point_from_cct, ViewingConditions, ViewingConditionsBuilder},
+};
+use crate::utils::color_utils::{lstar_from_argb, lstar_from_y};
 
 /// HCT, hue, chroma, and tone. A color system that provides a perceptually
 /// accurate color measurement system that can also accurately render what
 /// colors will appear as in different lighting environments.
-#[derive(Debug, PartialEq)]
+///
+/// Hue and chroma are measured in the [`ViewingConditions`] the color was
+/// created with, [`ViewingConditions::default`] unless one of the
+/// `_in_viewing_conditions` constructors is used. Tone is L*, which does not
+/// depend on viewing conditions.
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Hct {
-    pub hue: f64,
-    pub chroma: f64,
-    pub tone: f64,
+    hue: f64,
+    chroma: f64,
+    tone: f64,
     argb: u32,
+    viewing_conditions: ViewingConditions,
 }
 
 impl Hct {
@@ -26,24 +36,162 @@ impl Hct {
         Hct::_new(solve_to_int(hue, chroma, tone))
     }
 
+    /// Like [`Hct::new`], with `hue` and `chroma` measured in
+    /// `viewing_conditions`.
+    pub fn new_in_viewing_conditions(
+        hue: f64,
+        chroma: f64,
+        tone: f64,
+        viewing_conditions: &ViewingConditions,
+    ) -> Hct {
+        Hct::_new_in_viewing_conditions(
+            solve_to_int_in_viewing_conditions(hue, chroma, tone, viewing_conditions),
+            *viewing_conditions,
+        )
+    }
+
+    /// 0 <= hue < 360
+    pub fn hue(&self) -> f64 {
+        self.hue
+    }
+
+    /// 0 <= chroma <= ?; Informally, colorfulness. Chroma has a different
+    /// maximum for any given hue and tone.
+    pub fn chroma(&self) -> f64 {
+        self.chroma
+    }
+
+    /// Lightness. Ranges from 0 to 100.
+    pub fn tone(&self) -> f64 {
+        self.tone
+    }
+
     pub fn to_int(&self) -> u32 {
         self.argb
     }
 
+    /// The viewing conditions hue and chroma are measured in.
+    pub fn viewing_conditions(&self) -> &ViewingConditions {
+        &self.viewing_conditions
+    }
+
+    /// 0 <= `new_hue` < 360; invalid values are corrected.
+    /// After setting hue, the color is mapped from HCT to the more limited
+    /// sRGB gamut for display. This will change its ARGB/integer
+    /// representation. If the HCT color is outside of the sRGB gamut, chroma
+    /// will decrease until it is inside the gamut.
+    pub fn set_hue(&mut self, new_hue: f64) {
+        self.set_internal_state(solve_to_int_in_viewing_conditions(
+            new_hue,
+            self.chroma,
+            self.tone,
+            &self.viewing_conditions,
+        ));
+    }
+
+    /// 0 <= `new_chroma` <= ?
+    /// After setting chroma, the color is mapped from HCT to the more limited
+    /// sRGB gamut for display. This will change its ARGB/integer
+    /// representation. If the HCT color is outside of the sRGB gamut, chroma
+    /// will decrease until it is inside the gamut.
+    pub fn set_chroma(&mut self, new_chroma: f64) {
+        self.set_internal_state(solve_to_int_in_viewing_conditions(
+            self.hue,
+            new_chroma,
+            self.tone,
+            &self.viewing_conditions,
+        ));
+    }
+
+    /// 0 <= `new_tone` <= 100; invalid values are corrected.
+    /// After setting tone, the color is mapped from HCT to the more limited
+    /// sRGB gamut for display. This will change its ARGB/integer
+    /// representation. If the HCT color is outside of the sRGB gamut, chroma
+    /// will decrease until it is inside the gamut.
+    pub fn set_tone(&mut self, new_tone: f64) {
+        self.set_internal_state(solve_to_int_in_viewing_conditions(
+            self.hue,
+            self.chroma,
+            new_tone,
+            &self.viewing_conditions,
+        ));
+    }
+
+    /// Translate a color into different [`ViewingConditions`].
+    ///
+    /// Colors change appearance. They look different with lights on versus
+    /// off, the same color, as in hex code, on white looks different when on
+    /// black. This is called color relativity, most famously explicated by
+    /// Josef Albers in Interaction of Color.
+    ///
+    /// In color science, color appearance models can account for this and
+    /// calculate the appearance of a color in different settings. HCT is based
+    /// on CAM16, a color appearance model, and uses it to make these
+    /// calculations.
+    ///
+    /// See [`ViewingConditions::new`] for parameters affecting color
+    /// appearance.
+    ///
+    /// The returned color is measured in default viewing conditions.
+    pub fn in_viewing_conditions(&self, viewing_conditions: &ViewingConditions) -> Hct {
+        // 1. Use CAM16 to find XYZ coordinates of color in specified viewing
+        // conditions.
+        let cam16 = Cam16::from_int_in_viewing_conditions(self.to_int(), &self.viewing_conditions);
+        let viewed_in_vc = cam16.xyz_in_viewing_conditions(viewing_conditions);
+
+        // 2. Create CAM16 of those XYZ coordinates in default viewing
+        // conditions.
+        let recast_in_vc = Cam16::from_xyz_in_viewing_conditions(
+            viewed_in_vc[0],
+            viewed_in_vc[1],
+            viewed_in_vc[2],
+            ViewingConditions::default_ref(),
+        );
+
+        // 3. Create HCT from:
+        // - CAM16 using default viewing conditions with XYZ coordinates in
+        //   specified viewing conditions.
+        // - L* converted from Y in XYZ coordinates in specified viewing
+        //   conditions.
+        Hct::new(
+            recast_in_vc.hue,
+            recast_in_vc.chroma,
+            lstar_from_y(viewed_in_vc[1]),
+        )
+    }
+
     fn _new(argb: u32) -> Hct {
-        let cam = Cam16::from_int(argb);
+        Hct::_new_in_viewing_conditions(argb, ViewingConditions::default())
+    }
+
+    fn _new_in_viewing_conditions(argb: u32, viewing_conditions: ViewingConditions) -> Hct {
+        let cam = Cam16::from_int_in_viewing_conditions(argb, &viewing_conditions);
         Hct {
             hue: cam.hue,
             chroma: cam.chroma,
             tone: lstar_from_argb(argb),
             argb,
+            viewing_conditions,
         }
     }
 
+    fn set_internal_state(&mut self, argb: u32) {
+        *self = Hct::_new_in_viewing_conditions(argb, self.viewing_conditions);
+    }
+
     /// HCT representation of `argb`.
     pub fn from_int(argb: u32) -> Hct {
         Hct::_new(argb)
     }
+
+    /// HCT representation of `argb`, with hue and chroma measured in
+    /// `viewing_conditions`.
+    pub fn from_int_in_viewing_conditions(
+        argb: u32,
+        viewing_conditions: &ViewingConditions,
+    ) -> Hct {
+        Hct::_new_in_viewing_conditions(argb, *viewing_conditions)
+    }
 }
 
 #[cfg(test)]
@@ -62,12 +210,12 @@ mod test {
     const BLUE: u32 = 0xff0000ff;
 
     fn color_is_on_boundary(argb: u32) -> bool {
-        return red_from_argb(argb) == 0
+        red_from_argb(argb) == 0
             || red_from_argb(argb) == 255
             || green_from_argb(argb) == 0
             || green_from_argb(argb) == 255
             || blue_from_argb(argb) == 0
-            || blue_from_argb(argb) == 255;
+            || blue_from_argb(argb) == 255
     }
 
     #[test]
@@ -203,9 +351,9 @@ mod test {
                         "Chroma should be close or less for {}",
                         hct_request_description
                     );
-                    if (hct_color.chroma < chroma as f64 - 2.5) {
+                    if hct_color.chroma < chroma as f64 - 2.5 {
                         assert!(
-                            color_is_on_boundary(hct_color.to_int()) == true,
+                            color_is_on_boundary(hct_color.to_int()),
                             "HCT request for non-sRGB color should return 
                               a color on the boundary of the sRGB cube 
                               for {}, but got {:X} instead",
@@ -228,4 +376,93 @@ mod test {
             assert_eq!(reconstructed_argb, argb);
         }
     }
+
+    #[test]
+    fn setters_update_argb() {
+        let mut hct = Hct::from_int(BLUE);
+
+        hct.set_hue(120.0);
+        assert_eq!(
+            hct.to_int(),
+            Hct::new(120.0, hct.chroma(), hct.tone()).to_int()
+        );
+        assert_ne!(hct.to_int(), BLUE);
+
+        let mut hct = Hct::from_int(RED);
+        hct.set_chroma(16.0);
+        assert_eq!(hct.to_int(), Hct::new(hct.hue(), 16.0, hct.tone()).to_int());
+        assert_approx_eq!(hct.chroma(), 16.0, 0.05);
+
+        let mut hct = Hct::from_int(GREEN);
+        hct.set_tone(30.0);
+        assert_approx_eq!(hct.tone(), 30.0, 0.02);
+        assert_approx_eq!(lstar_from_argb(hct.to_int()), 30.0, 0.02);
+    }
+
+    fn background_conditions(background_lstar: f64) -> ViewingConditions {
+        let default = ViewingConditions::default();
+        ViewingConditions::new(
+            default.white_point,
+            default.adapting_luminance,
+            background_lstar,
+            default.surround,
+            default.discounting_illuminant,
+        )
+    }
+
+    #[test]
+    fn color_relativity() {
+        let black = background_conditions(0.0);
+        let white = background_conditions(100.0);
+        let cases = [
+            (RED, &black, 0xff9f5c51),
+            (RED, &white, 0xffff5d48),
+            (GREEN, &black, 0xffacd69d),
+            (GREEN, &white, 0xff8eff77),
+            (BLUE, &black, 0xff343654),
+            (WHITE, &black, 0xffffffff),
+            (WHITE, &white, 0xffffffff),
+            (0xff777777, &black, 0xff605f5f),
+            (0xff777777, &white, 0xff8e8e8e),
+            (BLACK, &black, 0xff000000),
+            (BLACK, &white, 0xff000000),
+        ];
+        for (color, viewing_conditions, expected) in cases {
+            let viewed = Hct::from_int(color).in_viewing_conditions(viewing_conditions);
+            assert_eq!(viewed.to_int(), expected, "{:X}", color);
+        }
+    }
+
+    #[test]
+    fn hct_in_viewing_conditions_preserves_original_color() {
+        let dim_d50 = ViewingConditions::new([96.422, 100.0, 82.521], 40.0, 20.0, 1.0, false);
+        for red in (0..=255).step_by(15) {
+            for green in (0..=255).step_by(15) {
+                for blue in (0..=255).step_by(15) {
+                    let argb = 0xff000000 | (red << 16) | (green << 8) | blue;
+                    let hct = Hct::from_int_in_viewing_conditions(argb, &dim_d50);
+                    let reconstructed = Hct::new_in_viewing_conditions(
+                        hct.hue(),
+                        hct.chroma(),
+                        hct.tone(),
+                        &dim_d50,
+                    );
+                    assert_eq!(reconstructed.to_int(), argb);
+                    assert_eq!(reconstructed.viewing_conditions(), &dim_d50);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn setters_keep_viewing_conditions() {
+        let dim = ViewingConditions::new([95.047, 100.0, 108.883], 11.72, 20.0, 1.0, false);
+        let mut hct = Hct::new_in_viewing_conditions(200.0, 30.0, 50.0, &dim);
+        hct.set_tone(70.0);
+        assert_eq!(hct.viewing_conditions(), &dim);
+        let measured = Hct::from_int_in_viewing_conditions(hct.to_int(), &dim);
+        assert_approx_eq!(measured.hue(), 200.0, 0.01);
+        assert_approx_eq!(measured.chroma(), 30.0, 0.01);
+        assert_approx_eq!(measured.tone(), 70.0, 0.01);
+    }
 }
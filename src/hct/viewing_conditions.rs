@@ -1,4 +1,15 @@
-use crate::utils::{color_utils::y_from_lstar, math_utils::lerp};
+use std::{f64::consts::PI, sync::OnceLock};
+
+use crate::{
+    error::ArgumentError,
+    utils::{color_utils::y_from_lstar, math_utils::lerp},
+};
+
+/// The CIE standard illuminant D65 white point, used by sRGB.
+const WHITE_POINT_D65: [f64; 3] = [95.047, 100.0, 108.883];
+
+/// The CIE standard illuminant D50 white point, used by ICC print workflows.
+const WHITE_POINT_D50: [f64; 3] = [96.422, 100.0, 82.521];
 
 /// In traditional color spaces, a color can be identified solely by the
 /// observer's measurement of the color. Color appearance models such as CAM16
@@ -11,7 +22,7 @@ use crate::utils::{color_utils::y_from_lstar, math_utils::lerp};
 ///
 /// This class caches intermediate values of the CAM16 conversion process that
 /// depend only on viewing conditions, enabling speed ups.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ViewingConditions {
     pub white_point: [f64; 3],
     pub adapting_luminance: f64,
@@ -32,18 +43,49 @@ pub struct ViewingConditions {
     pub z: f64,
 }
 
+/// Adapting luminance, in cd/m², of a surface with L* 50 lit by `lux`.
+fn adapting_luminance_from_lux(lux: f64) -> f64 {
+    lux / PI * y_from_lstar(50.0) / 100.0
+}
+
+/// The default viewing conditions, computed on first use.
+static DEFAULT: OnceLock<ViewingConditions> = OnceLock::new();
+
 impl Default for ViewingConditions {
     fn default() -> ViewingConditions {
-        ViewingConditions::new(
-            [95.047, 100.0, 108.883],
-            200.0 / std::f64::consts::PI * y_from_lstar(50.0) / 100.0,
-            50.0,
-            2.0,
-            false,
-        )
+        *ViewingConditions::default_ref()
     }
 }
 impl ViewingConditions {
+    /// A shared reference to [`ViewingConditions::default`], which is only
+    /// computed once.
+    pub(crate) fn default_ref() -> &'static ViewingConditions {
+        DEFAULT.get_or_init(|| {
+            ViewingConditions::new(
+                WHITE_POINT_D65,
+                adapting_luminance_from_lux(200.0),
+                50.0,
+                2.0,
+                false,
+            )
+        })
+    }
+
+    /// Create ViewingConditions from a simple, physically relevant, set of
+    /// parameters.
+    ///
+    /// `white_point` is the color of the light source in XYZ, with Y 100.
+    /// `adapting_luminance` is the luminance of the adapting field, in cd/m²;
+    /// values <= 0 fall back to the default. `background_lstar` is the L* of
+    /// the area surrounding the color. `surround` ranges from 0 (dark, ex. a
+    /// movie theater) through 1 (dim, ex. a phone at night) to 2 (average, ex.
+    /// daylight). `discounting_illuminant` is whether the eye fully adapts to
+    /// the light source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `surround` is outside 0..=2. Use
+    /// [`ViewingConditions::builder`] to validate untrusted parameters.
     pub fn new(
         white_point: [f64; 3],
         adapting_luminance: f64,
@@ -54,10 +96,10 @@ impl ViewingConditions {
         let adapting_luminance = if adapting_luminance > 0.0 {
             adapting_luminance
         } else {
-            200.0 / std::f64::consts::PI * y_from_lstar(50.0) / 100.0
+            adapting_luminance_from_lux(200.0)
         };
 
-        let background_lstar = 30f64.max(background_lstar);
+        let background_lstar = 0.1f64.max(background_lstar);
 
         let r_w =
             white_point[0] * 0.401288 + white_point[1] * 0.650173 + white_point[2] * -0.051461;
@@ -82,13 +124,7 @@ impl ViewingConditions {
         } else {
             f * (1.0 - ((1.0 / 3.6) * ((-adapting_luminance - 42.0) / 92.0).exp()))
         };
-        d = if d > 1.0 {
-            1.0
-        } else if d < 0.0 {
-            0.0
-        } else {
-            d
-        };
+        d = d.clamp(0.0, 1.0);
         let nc = f;
 
         let rgb_d = [
@@ -151,4 +187,286 @@ impl ViewingConditions {
             z,
         }
     }
+
+    /// Returns a builder starting from the default viewing conditions, which
+    /// validates its parameters instead of panicking.
+    pub fn builder() -> ViewingConditionsBuilder {
+        ViewingConditionsBuilder::default()
+    }
+
+    /// sRGB-like viewing conditions: a D65 white point, 200 lux, a mid-gray
+    /// background and an average surround. Same as
+    /// [`ViewingConditions::default`].
+    pub fn s_rgb() -> ViewingConditions {
+        ViewingConditions::default()
+    }
+
+    /// sRGB viewing conditions with a dim surround, ex. a screen viewed in a
+    /// dim room.
+    pub fn dim_surround() -> ViewingConditions {
+        ViewingConditions::new(
+            WHITE_POINT_D65,
+            adapting_luminance_from_lux(200.0),
+            50.0,
+            1.0,
+            false,
+        )
+    }
+
+    /// sRGB viewing conditions with a dark surround, ex. a projector in a dark
+    /// room.
+    pub fn dark_surround() -> ViewingConditions {
+        ViewingConditions::new(
+            WHITE_POINT_D65,
+            adapting_luminance_from_lux(200.0),
+            50.0,
+            0.0,
+            false,
+        )
+    }
+
+    /// Print viewing conditions: a D50 white point, 500 lux (ISO 3664 P2), a
+    /// mid-gray background and an average surround.
+    pub fn d50_print() -> ViewingConditions {
+        ViewingConditions::new(
+            WHITE_POINT_D50,
+            adapting_luminance_from_lux(500.0),
+            50.0,
+            2.0,
+            false,
+        )
+    }
+
+    /// Default viewing conditions with the background at `lstar`.
+    ///
+    /// Returns an error if `lstar` is not within 0..=100.
+    pub fn from_background_lstar(lstar: f64) -> Result<ViewingConditions, ArgumentError> {
+        ViewingConditions::builder().background_lstar(lstar).build()
+    }
+
+    /// Default viewing conditions with a white point of the given correlated
+    /// color temperature.
+    ///
+    /// Returns an error if `kelvin` is not within 1667..=25000.
+    pub fn from_cct(kelvin: f64) -> Result<ViewingConditions, ArgumentError> {
+        ViewingConditions::builder()
+            .white_point(white_point_from_cct(kelvin)?)
+            .build()
+    }
+}
+
+/// The XYZ white point, with Y 100, of light with the correlated color
+/// temperature `kelvin`.
+///
+/// Uses the Planckian locus approximation of Kang et al. (2002) below 4000K,
+/// and the CIE daylight locus from 4000K, so 6504K is close to D65 and 5003K
+/// is close to D50.
+///
+/// Returns an error if `kelvin` is not within 1667..=25000.
+pub fn white_point_from_cct(kelvin: f64) -> Result<[f64; 3], ArgumentError> {
+    if !(1667.0..=25000.0).contains(&kelvin) {
+        return Err(ArgumentError::new(format!(
+            "Invalid argument (kelvin: {}): must be between 1667 and 25000",
+            kelvin
+        )));
+    }
+    let t = kelvin;
+    let (x, y) = if t < 4000.0 {
+        let x = -0.2661239e9 / (t * t * t) - 0.2343589e6 / (t * t) + 0.8776956e3 / t + 0.179910;
+        let y = if t < 2222.0 {
+            -1.1063814 * x * x * x - 1.34811020 * x * x + 2.18555832 * x - 0.20219683
+        } else {
+            -0.9549476 * x * x * x - 1.37418593 * x * x + 2.09137015 * x - 0.16748867
+        };
+        (x, y)
+    } else {
+        let x = if t <= 7000.0 {
+            -4.6070e9 / (t * t * t) + 2.9678e6 / (t * t) + 0.09911e3 / t + 0.244063
+        } else {
+            -2.0064e9 / (t * t * t) + 1.9018e6 / (t * t) + 0.24748e3 / t + 0.237040
+        };
+        (x, -3.0 * x * x + 2.870 * x - 0.275)
+    };
+    Ok([x / y * 100.0, 100.0, (1.0 - x - y) / y * 100.0])
+}
+
+/// Builds [`ViewingConditions`], returning an error for parameters that
+/// [`ViewingConditions::new`] would panic on or silently correct.
+///
+/// Starts from the default viewing conditions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewingConditionsBuilder {
+    white_point: [f64; 3],
+    adapting_luminance: f64,
+    background_lstar: f64,
+    surround: f64,
+    discounting_illuminant: bool,
+}
+
+impl Default for ViewingConditionsBuilder {
+    fn default() -> ViewingConditionsBuilder {
+        ViewingConditionsBuilder {
+            white_point: WHITE_POINT_D65,
+            adapting_luminance: adapting_luminance_from_lux(200.0),
+            background_lstar: 50.0,
+            surround: 2.0,
+            discounting_illuminant: false,
+        }
+    }
+}
+
+impl ViewingConditionsBuilder {
+    /// The color of the light source in XYZ. Components must be positive.
+    pub fn white_point(mut self, white_point: [f64; 3]) -> ViewingConditionsBuilder {
+        self.white_point = white_point;
+        self
+    }
+
+    /// The luminance of the adapting field, in cd/m². Must be positive.
+    pub fn adapting_luminance(mut self, adapting_luminance: f64) -> ViewingConditionsBuilder {
+        self.adapting_luminance = adapting_luminance;
+        self
+    }
+
+    /// The L* of the area surrounding the color, 0..=100.
+    pub fn background_lstar(mut self, background_lstar: f64) -> ViewingConditionsBuilder {
+        self.background_lstar = background_lstar;
+        self
+    }
+
+    /// 0 (dark) through 1 (dim) to 2 (average).
+    pub fn surround(mut self, surround: f64) -> ViewingConditionsBuilder {
+        self.surround = surround;
+        self
+    }
+
+    /// Whether the eye fully adapts to the light source.
+    pub fn discounting_illuminant(
+        mut self,
+        discounting_illuminant: bool,
+    ) -> ViewingConditionsBuilder {
+        self.discounting_illuminant = discounting_illuminant;
+        self
+    }
+
+    pub fn build(self) -> Result<ViewingConditions, ArgumentError> {
+        if !self.white_point.iter().all(|c| c.is_finite() && *c > 0.0) {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (white_point: {:?}): components must be positive",
+                self.white_point
+            )));
+        }
+        if !(self.adapting_luminance.is_finite() && self.adapting_luminance > 0.0) {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (adapting_luminance: {}): must be positive",
+                self.adapting_luminance
+            )));
+        }
+        if !(0.0..=100.0).contains(&self.background_lstar) {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (background_lstar: {}): must be between 0 and 100",
+                self.background_lstar
+            )));
+        }
+        if !(0.0..=2.0).contains(&self.surround) {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (surround: {}): must be between 0 and 2",
+                self.surround
+            )));
+        }
+        Ok(ViewingConditions::new(
+            self.white_point,
+            self.adapting_luminance,
+            self.background_lstar,
+            self.surround,
+            self.discounting_illuminant,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx_eq::assert_approx_eq;
+
+    use super::{white_point_from_cct, ViewingConditions, WHITE_POINT_D50, WHITE_POINT_D65};
+
+    #[test]
+    fn builder_defaults_to_s_rgb() {
+        assert_eq!(
+            ViewingConditions::builder().build().unwrap(),
+            ViewingConditions::default()
+        );
+        assert_eq!(ViewingConditions::s_rgb(), ViewingConditions::default());
+    }
+
+    #[test]
+    fn builder_rejects_invalid_parameters() {
+        assert!(ViewingConditions::builder().surround(3.0).build().is_err());
+        assert!(ViewingConditions::builder().surround(-0.1).build().is_err());
+        assert!(ViewingConditions::builder()
+            .surround(f64::NAN)
+            .build()
+            .is_err());
+        assert!(ViewingConditions::builder()
+            .adapting_luminance(0.0)
+            .build()
+            .is_err());
+        assert!(ViewingConditions::builder()
+            .background_lstar(101.0)
+            .build()
+            .is_err());
+        assert!(ViewingConditions::builder()
+            .white_point([95.047, 0.0, 108.883])
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(ViewingConditions::dim_surround().surround, 1.0);
+        assert_eq!(ViewingConditions::dark_surround().surround, 0.0);
+        let print = ViewingConditions::d50_print();
+        assert_eq!(print.white_point, WHITE_POINT_D50);
+        assert!(print.adapting_luminance > ViewingConditions::default().adapting_luminance);
+    }
+
+    #[test]
+    fn from_background_lstar() {
+        let vc = ViewingConditions::from_background_lstar(20.0).unwrap();
+        assert_eq!(vc.background_lstar, 20.0);
+        assert!(ViewingConditions::from_background_lstar(-1.0).is_err());
+    }
+
+    #[test]
+    fn low_background_lstar() {
+        let default = ViewingConditions::default();
+        let with_background = |background_lstar| {
+            ViewingConditions::new(
+                default.white_point,
+                default.adapting_luminance,
+                background_lstar,
+                default.surround,
+                default.discounting_illuminant,
+            )
+        };
+        let dark = with_background(10.0);
+        assert_eq!(dark.background_lstar, 10.0);
+        assert!(dark.nbb > with_background(30.0).nbb);
+        assert_eq!(with_background(0.0).background_lstar, 0.1);
+    }
+
+    #[test]
+    fn white_point_from_cct_matches_standard_illuminants() {
+        let d65 = white_point_from_cct(6504.0).unwrap();
+        for i in 0..3 {
+            assert_approx_eq!(d65[i], WHITE_POINT_D65[i], 0.005);
+        }
+        let d50 = white_point_from_cct(5003.0).unwrap();
+        for i in 0..3 {
+            assert_approx_eq!(d50[i], WHITE_POINT_D50[i], 0.005);
+        }
+        assert!(white_point_from_cct(1000.0).is_err());
+        assert!(ViewingConditions::from_cct(30000.0).is_err());
+        assert!(ViewingConditions::from_cct(2700.0).is_ok());
+    }
 }
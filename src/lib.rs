@@ -1,11 +1,18 @@
 pub mod blend;
+pub mod dislike;
+pub mod dynamiccolor;
 pub mod error;
 pub mod hct;
 pub mod palette;
 pub mod quantize;
 pub mod scheme;
 pub mod score;
+pub mod temperature;
 pub mod utils;
 
 pub use crate::scheme::Scheme;
 pub use crate::score::score;
+pub use crate::utils::{
+    image_utils::source_color_from_pixels,
+    theme_utils::{theme_from_pixels, Theme},
+};
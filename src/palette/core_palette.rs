@@ -1,4 +1,7 @@
-use crate::{hct::Cam16, palette::tonal_palette::TonalPalette};
+use crate::{
+    hct::{Cam16, ViewingConditions},
+    palette::tonal_palette::TonalPalette,
+};
 
 /// An intermediate concept between the key color for a UI theme, and a full
 /// color scheme. 5 tonal palettes are generated, all except one use the same
@@ -17,14 +20,25 @@ impl CorePalette {
     pub const SIZE: usize = 5;
     /// Create a [`CorePalette`] from a source ARGB color.
     pub fn of(argb: u32) -> CorePalette {
-        let cam = Cam16::from_int(argb);
+        CorePalette::of_in_viewing_conditions(argb, ViewingConditions::default_ref())
+    }
+
+    /// Create a [`CorePalette`] from a source ARGB color, with hue and chroma
+    /// measured in `viewing_conditions`.
+    pub fn of_in_viewing_conditions(
+        argb: u32,
+        viewing_conditions: &ViewingConditions,
+    ) -> CorePalette {
+        let cam = Cam16::from_int_in_viewing_conditions(argb, viewing_conditions);
+        let palette =
+            |hue, chroma| TonalPalette::of_in_viewing_conditions(hue, chroma, viewing_conditions);
         CorePalette {
-            primary: TonalPalette::of(cam.hue, (48f64).max(cam.chroma)),
-            secondary: TonalPalette::of(cam.hue, 16.0),
-            tertiary: TonalPalette::of(cam.hue + 60.0, 24.0),
-            neutral: TonalPalette::of(cam.hue, 4.0),
-            neutral_variant: TonalPalette::of(cam.hue, 8.0),
-            error: TonalPalette::of(25.0, 84.0),
+            primary: palette(cam.hue, (48f64).max(cam.chroma)),
+            secondary: palette(cam.hue, 16.0),
+            tertiary: palette(cam.hue + 60.0, 24.0),
+            neutral: palette(cam.hue, 4.0),
+            neutral_variant: palette(cam.hue, 8.0),
+            error: palette(25.0, 84.0),
         }
     }
 
@@ -82,6 +96,17 @@ impl CorePalette {
     }
 }
 
+// Returns a partition from a list.
+//
+// For example, given a list with 2 partitions of size 3.
+// range = [1, 2, 3, 4, 5, 6];
+//
+// range.get_partition(0, 3) // [1, 2, 3]
+// range.get_partition(1, 3) // [4, 5, 6]
+fn get_partition(list: &[u32], partition_number: usize, partition_size: usize) -> &[u32] {
+    &list[partition_number * partition_size..(partition_number + 1) * partition_size]
+}
+
 #[cfg(test)]
 mod test {
     use super::CorePalette;
@@ -107,14 +132,3 @@ mod test {
         assert_ne!(core_palette_b, core_palette_c);
     }
 }
-
-// Returns a partition from a list.
-//
-// For example, given a list with 2 partitions of size 3.
-// range = [1, 2, 3, 4, 5, 6];
-//
-// range.get_partition(0, 3) // [1, 2, 3]
-// range.get_partition(1, 3) // [4, 5, 6]
-fn get_partition(list: &[u32], partition_number: usize, partition_size: usize) -> &[u32] {
-    &list[partition_number * partition_size..(partition_number + 1) * partition_size]
-}
@@ -1,4 +1,7 @@
-use crate::{error::ArgumentError, hct::Hct};
+use crate::{
+    error::ArgumentError,
+    hct::{Hct, ViewingConditions},
+};
 use indexmap::{IndexMap, IndexSet};
 use std::fmt::Debug;
 
@@ -8,21 +11,23 @@ use std::fmt::Debug;
 /// This class can be instantiated in two ways:
 /// 1. [`TonalPalette::of`] From hue and chroma. (preferred)
 /// 2. [`TonalPalette::from_list`] From a fixed-size ([`TonalPalette::COMMON_SIZE`]) list of ints
-/// representing ARBG colors. Correctness (constant hue and chroma) of the input
-/// is not enforced. [`TonalPalette::tone`] will only return the input colors corresponding to
-/// [`TonalPalette::COMMON_TONES`].
-
+///    representing ARBG colors. Correctness (constant hue and chroma) of the input
+///    is not enforced. [`TonalPalette::tone`] will only return the input colors corresponding to
+///    [`TonalPalette::COMMON_TONES`].
 #[derive(Debug)]
 pub struct TonalPalette {
     hue: Option<f64>,
     chroma: Option<f64>,
     cache: IndexMap<u32, u32>,
+    viewing_conditions: ViewingConditions,
 }
 
 impl PartialEq for TonalPalette {
     fn eq(&self, other: &Self) -> bool {
         if self.hue.is_some() && self.chroma.is_some() {
-            self.hue == other.hue && self.chroma == other.chroma
+            self.hue == other.hue
+                && self.chroma == other.chroma
+                && self.viewing_conditions == other.viewing_conditions
         } else {
             let self_cache_set = self.cache.values().copied().collect::<IndexSet<u32>>();
             let other_cache_set = other.cache.values().copied().collect::<IndexSet<u32>>();
@@ -39,11 +44,16 @@ impl TonalPalette {
     /// Commonly-used tone values.
     pub const COMMON_TONES: [u32; 13] = [0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 95, 99, 100];
     pub const COMMON_SIZE: usize = Self::COMMON_TONES.len();
-    fn from_hue_and_chroma(hue: f64, chroma: f64) -> TonalPalette {
+    fn from_hue_and_chroma(
+        hue: f64,
+        chroma: f64,
+        viewing_conditions: ViewingConditions,
+    ) -> TonalPalette {
         TonalPalette {
             hue: Some(hue),
             chroma: Some(chroma),
             cache: IndexMap::new(),
+            viewing_conditions,
         }
     }
 
@@ -52,18 +62,39 @@ impl TonalPalette {
             cache,
             hue: None,
             chroma: None,
+            viewing_conditions: ViewingConditions::default(),
         }
     }
 
     ///Create tones using the HCT hue and chroma from a color.
     pub fn from_int(argb: u32) -> TonalPalette {
-        let hct = Hct::from_int(argb);
-        TonalPalette::from_hue_and_chroma(hct.hue, hct.chroma)
+        TonalPalette::from_hct(Hct::from_int(argb))
+    }
+
+    /// Create tones using the hue and chroma of `hct`, in the viewing
+    /// conditions `hct` is measured in.
+    pub fn from_hct(hct: Hct) -> TonalPalette {
+        TonalPalette::from_hue_and_chroma(hct.hue(), hct.chroma(), *hct.viewing_conditions())
     }
 
     /// Create colors using `hue` and `chroma`.
     pub fn of(hue: f64, chroma: f64) -> TonalPalette {
-        TonalPalette::from_hue_and_chroma(hue, chroma)
+        TonalPalette::from_hue_and_chroma(hue, chroma, ViewingConditions::default())
+    }
+
+    /// Create colors using `hue` and `chroma` measured in
+    /// `viewing_conditions`.
+    pub fn of_in_viewing_conditions(
+        hue: f64,
+        chroma: f64,
+        viewing_conditions: &ViewingConditions,
+    ) -> TonalPalette {
+        TonalPalette::from_hue_and_chroma(hue, chroma, *viewing_conditions)
+    }
+
+    /// The viewing conditions the palette's hue and chroma are measured in.
+    pub fn viewing_conditions(&self) -> &ViewingConditions {
+        &self.viewing_conditions
     }
 
     /// Create colors from a fixed-size list of ARGB color ints.
@@ -109,8 +140,108 @@ impl TonalPalette {
             self.chroma.unwrap()
         };
         let tone_entry = self.cache.entry(tone_value);
-        Ok(*tone_entry
-            .or_insert_with(|| Hct::new(self.hue.unwrap(), chroma, tone_value as f64).to_int()))
+        Ok(*tone_entry.or_insert_with(|| {
+            Hct::new_in_viewing_conditions(
+                self.hue.unwrap(),
+                chroma,
+                tone_value as f64,
+                &self.viewing_conditions,
+            )
+            .to_int()
+        }))
+    }
+
+    /// Returns the HCT color at `tone` in this palette.
+    ///
+    /// Unlike [`TonalPalette::tone`], `tone` may be fractional and is not
+    /// cached. For palettes created with [`TonalPalette::from_list`], the hue and
+    /// chroma are approximated from the listed colors.
+    pub fn get_hct(&self, tone: f64) -> Hct {
+        let (hue, chroma) = self.hue_and_chroma();
+        Hct::new_in_viewing_conditions(hue, chroma, tone, &self.viewing_conditions)
+    }
+
+    /// The hue of the palette.
+    pub fn hue(&self) -> f64 {
+        self.hue_and_chroma().0
+    }
+
+    /// The chroma of the palette.
+    pub fn chroma(&self) -> f64 {
+        self.hue_and_chroma().1
+    }
+
+    /// The key color is the first tone, starting from T50, that matches the
+    /// palette's chroma.
+    pub fn key_color(&self) -> Hct {
+        let (hue, chroma) = self.hue_and_chroma();
+        let start_tone = 50.0;
+        let mut smallest_delta_hct =
+            Hct::new_in_viewing_conditions(hue, chroma, start_tone, &self.viewing_conditions);
+        let mut smallest_delta = (smallest_delta_hct.chroma() - chroma).abs();
+        // Starting from T50, check T+/-delta to see if they match the requested
+        // chroma.
+        //
+        // Starts from T50 because T50 has the most chroma available, on
+        // average. Thus it is most likely to have a direct answer and minimize
+        // iteration.
+        for delta in 1..50 {
+            // Termination condition rounding instead of minimizing delta to avoid
+            // case where requested chroma is 16.51, and the closest chroma is 16.49.
+            // Error is minimized, but when rounded and displayed, requesting chroma
+            // of 16.51, and getting 16.49, is not desirable.
+            if chroma.round() == smallest_delta_hct.chroma().round() {
+                return smallest_delta_hct;
+            }
+
+            let hct_add = Hct::new_in_viewing_conditions(
+                hue,
+                chroma,
+                start_tone + delta as f64,
+                &self.viewing_conditions,
+            );
+            let hct_add_delta = (hct_add.chroma() - chroma).abs();
+            if hct_add_delta < smallest_delta {
+                smallest_delta = hct_add_delta;
+                smallest_delta_hct = hct_add;
+            }
+
+            let hct_subtract = Hct::new_in_viewing_conditions(
+                hue,
+                chroma,
+                start_tone - delta as f64,
+                &self.viewing_conditions,
+            );
+            let hct_subtract_delta = (hct_subtract.chroma() - chroma).abs();
+            if hct_subtract_delta < smallest_delta {
+                smallest_delta = hct_subtract_delta;
+                smallest_delta_hct = hct_subtract;
+            }
+        }
+        smallest_delta_hct
+    }
+
+    // Palettes created from a list don't know their hue and chroma, so they are
+    // deduced from the listed color with the highest chroma.
+    fn hue_and_chroma(&self) -> (f64, f64) {
+        if let (Some(hue), Some(chroma)) = (self.hue, self.chroma) {
+            return (hue, chroma);
+        }
+        let mut best_hue = 0.0;
+        let mut best_chroma = 0.0;
+        for argb in self.cache.values() {
+            let hct = Hct::from_int(*argb);
+            // If the color is too close to white, its chroma may have been
+            // affected by gamut mapping, so it is ignored.
+            if hct.tone() > 98.0 {
+                continue;
+            }
+            if hct.chroma() > best_chroma {
+                best_hue = hct.hue();
+                best_chroma = hct.chroma();
+            }
+        }
+        (best_hue, best_chroma)
     }
 
     /// Returns a fixed-size list of ARGB color ints for common tone values.
@@ -154,7 +285,7 @@ mod test {
     #[test]
     fn of_constructor_as_list() {
         let hct = Hct::from_int(0xff0000ff);
-        let mut tones = TonalPalette::of(hct.hue, hct.chroma);
+        let mut tones = TonalPalette::of(hct.hue(), hct.chroma());
 
         assert_eq!(
             tones.as_list(),
@@ -194,13 +325,34 @@ mod test {
         assert!(tones.tone(3).is_err());
     }
 
+    #[test]
+    fn get_hct_matches_tone() {
+        let blue = TonalPalette::from_int(0xff0000ff);
+
+        assert_eq!(blue.get_hct(40.0).to_int(), 0xff343dff);
+        assert_eq!(blue.get_hct(0.0).to_int(), 0xff000000);
+    }
+
+    #[test]
+    fn key_color_with_exact_chroma() {
+        // Requested chroma is exactly achievable at a certain tone.
+        let palette = TonalPalette::of(50.0, 60.0);
+        let key_color = palette.key_color();
+
+        assert!((key_color.hue() - 50.0).abs() < 10.0);
+        assert!((key_color.chroma() - 60.0).abs() < 0.5);
+        // Tone might vary, but should be within the range from 0 to 100.
+        assert!(key_color.tone() > 0.0);
+        assert!(key_color.tone() < 100.0);
+    }
+
     #[test]
     fn equality() {
         let hct_ab = Hct::from_int(0xff0000ff);
-        let tones_a = TonalPalette::of(hct_ab.hue, hct_ab.chroma);
-        let tones_b = TonalPalette::of(hct_ab.hue, hct_ab.chroma);
+        let tones_a = TonalPalette::of(hct_ab.hue(), hct_ab.chroma());
+        let tones_b = TonalPalette::of(hct_ab.hue(), hct_ab.chroma());
         let hct_c = Hct::from_int(0xff123456);
-        let tones_c = TonalPalette::of(hct_c.hue, hct_c.chroma);
+        let tones_c = TonalPalette::of(hct_c.hue(), hct_c.chroma());
 
         assert_eq!(tones_a, tones_b);
         assert_ne!(tones_b, tones_c);
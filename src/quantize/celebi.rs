@@ -1,21 +1,237 @@
+use indexmap::IndexMap;
+
+use crate::error::ArgumentError;
+
 use super::{
-    point_provider_lab::PointProviderLab, wsmeans::QuantizerWsmeans, wu::QuantizerWu, Quantizer,
-    QuantizerResult,
+    point_provider::PointProvider,
+    point_provider_lab::PointProviderLab,
+    wsmeans::{QuantizerWsmeans, Seeding},
+    wu::QuantizerWu,
+    HistogramQuantizer, Quantizer, QuantizerInput, QuantizerResult,
 };
 
-pub struct QuantizerCelebi;
-impl Quantizer for QuantizerCelebi {
-    fn quantize(&mut self, pixels: &[u32], max_colors: u32) -> QuantizerResult {
-        let mut wu = QuantizerWu::new();
-        let wu_result = wu.quantize(pixels, max_colors);
-        let mut wsmeans = QuantizerWsmeans {
-            debug: true,
+/// Wu quantization to seed Wsmeans, which refines the clusters in the space
+/// of the options' point provider.
+pub struct QuantizerCelebi<P: PointProvider = PointProviderLab> {
+    options: CelebiOptions<P>,
+    wu: QuantizerWu,
+}
+
+impl Default for QuantizerCelebi {
+    fn default() -> QuantizerCelebi {
+        QuantizerCelebi::new(CelebiOptions::default())
+    }
+}
+
+impl<P: PointProvider> QuantizerCelebi<P> {
+    pub fn new(options: CelebiOptions<P>) -> QuantizerCelebi<P> {
+        let wu = QuantizerWu::with_index_bits(options.wu_bits)
+            .expect("wu_bits is validated by CelebiOptionsBuilder");
+        QuantizerCelebi { options, wu }
+    }
+
+    pub fn options(&self) -> &CelebiOptions<P> {
+        &self.options
+    }
+}
+
+impl<P: PointProvider + Clone> Quantizer for QuantizerCelebi<P> {
+    fn quantize_input(&mut self, input: &QuantizerInput, max_colors: u32) -> QuantizerResult {
+        let wu_result = self.wu.quantize_input(input, max_colors);
+        self.wsmeans(&wu_result).quantize_input(input, max_colors)
+    }
+}
+
+impl<P: PointProvider + Clone> QuantizerCelebi<P> {
+    /// Wsmeans with the options' settings, starting from Wu's clusters.
+    fn wsmeans(&self, wu_result: &QuantizerResult) -> QuantizerWsmeans<P> {
+        QuantizerWsmeans {
             starting_clusters: wu_result.color_to_count.keys().copied().collect(),
-            point_provider: PointProviderLab,
+            point_provider: self.options.point_provider.clone(),
+            max_iterations: self.options.max_iterations,
+            tolerance: self.options.tolerance,
+            seeding: self.options.seeding,
+            seed: self.options.seed,
+            return_input_pixel_to_cluster_pixel: self.options.return_input_pixel_to_cluster_pixel,
+        }
+    }
+}
+
+impl<P: PointProvider + Clone> HistogramQuantizer for QuantizerCelebi<P> {
+    fn quantize_histogram(
+        &mut self,
+        color_to_count: &IndexMap<u32, u32>,
+        max_colors: u32,
+    ) -> QuantizerResult {
+        let wu_result = self.wu.quantize_histogram(color_to_count, max_colors);
+        self.wsmeans(&wu_result)
+            .quantize_histogram(color_to_count, max_colors)
+    }
+}
+
+/// Settings for [`QuantizerCelebi`], created with [`CelebiOptions::builder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CelebiOptions<P: PointProvider = PointProviderLab> {
+    wu_bits: usize,
+    max_iterations: i32,
+    tolerance: f64,
+    seeding: Seeding,
+    seed: u64,
+    point_provider: P,
+    return_input_pixel_to_cluster_pixel: bool,
+}
+
+impl Default for CelebiOptions {
+    fn default() -> CelebiOptions {
+        CelebiOptions::builder().build().unwrap()
+    }
+}
+
+impl CelebiOptions {
+    /// Returns a builder starting from the defaults: 5 Wu bits, 5 Wsmeans
+    /// iterations, no tolerance, random seeding with
+    /// [`QuantizerWsmeans::DEFAULT_SEED`], [`PointProviderLab`] and no pixel
+    /// mapping.
+    pub fn builder() -> CelebiOptionsBuilder {
+        CelebiOptionsBuilder {
+            wu_bits: QuantizerWu::DEFAULT_INDEX_BITS,
             max_iterations: 5,
+            tolerance: 0.0,
+            seeding: Seeding::Random,
+            seed: QuantizerWsmeans::<PointProviderLab>::DEFAULT_SEED,
+            point_provider: PointProviderLab,
             return_input_pixel_to_cluster_pixel: false,
-        };
-        wsmeans.quantize(pixels, max_colors)
+        }
+    }
+}
+
+impl<P: PointProvider> CelebiOptions<P> {
+    pub fn wu_bits(&self) -> usize {
+        self.wu_bits
+    }
+
+    pub fn max_iterations(&self) -> i32 {
+        self.max_iterations
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    pub fn seeding(&self) -> Seeding {
+        self.seeding
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn point_provider(&self) -> &P {
+        &self.point_provider
+    }
+
+    pub fn return_input_pixel_to_cluster_pixel(&self) -> bool {
+        self.return_input_pixel_to_cluster_pixel
+    }
+}
+
+/// Builds [`CelebiOptions`], returning an error for settings the quantizers
+/// can't run with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CelebiOptionsBuilder<P: PointProvider = PointProviderLab> {
+    wu_bits: usize,
+    max_iterations: i32,
+    tolerance: f64,
+    seeding: Seeding,
+    seed: u64,
+    point_provider: P,
+    return_input_pixel_to_cluster_pixel: bool,
+}
+
+impl<P: PointProvider> CelebiOptionsBuilder<P> {
+    /// Bits per channel of the Wu histogram, from
+    /// [`QuantizerWu::MIN_INDEX_BITS`] to [`QuantizerWu::MAX_INDEX_BITS`].
+    pub fn wu_bits(mut self, wu_bits: usize) -> CelebiOptionsBuilder<P> {
+        self.wu_bits = wu_bits;
+        self
+    }
+
+    /// The maximum number of Wsmeans iterations. Must be at least 1.
+    pub fn max_iterations(mut self, max_iterations: i32) -> CelebiOptionsBuilder<P> {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// See [`QuantizerWsmeans::tolerance`]. Must not be negative.
+    pub fn tolerance(mut self, tolerance: f64) -> CelebiOptionsBuilder<P> {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// See [`QuantizerWsmeans::seeding`].
+    pub fn seeding(mut self, seeding: Seeding) -> CelebiOptionsBuilder<P> {
+        self.seeding = seeding;
+        self
+    }
+
+    /// See [`QuantizerWsmeans::seed`].
+    pub fn seed(mut self, seed: u64) -> CelebiOptionsBuilder<P> {
+        self.seed = seed;
+        self
+    }
+
+    /// The space Wsmeans clusters in.
+    pub fn point_provider<Q: PointProvider>(self, point_provider: Q) -> CelebiOptionsBuilder<Q> {
+        CelebiOptionsBuilder {
+            wu_bits: self.wu_bits,
+            max_iterations: self.max_iterations,
+            tolerance: self.tolerance,
+            seeding: self.seeding,
+            seed: self.seed,
+            point_provider,
+            return_input_pixel_to_cluster_pixel: self.return_input_pixel_to_cluster_pixel,
+        }
+    }
+
+    /// Whether to fill [`QuantizerResult::input_pixel_to_cluster_pixel`].
+    pub fn return_input_pixel_to_cluster_pixel(
+        mut self,
+        return_input_pixel_to_cluster_pixel: bool,
+    ) -> CelebiOptionsBuilder<P> {
+        self.return_input_pixel_to_cluster_pixel = return_input_pixel_to_cluster_pixel;
+        self
+    }
+
+    pub fn build(self) -> Result<CelebiOptions<P>, ArgumentError> {
+        if !(QuantizerWu::MIN_INDEX_BITS..=QuantizerWu::MAX_INDEX_BITS).contains(&self.wu_bits) {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (wu_bits: {}): must be between {} and {}",
+                self.wu_bits,
+                QuantizerWu::MIN_INDEX_BITS,
+                QuantizerWu::MAX_INDEX_BITS
+            )));
+        }
+        if self.max_iterations < 1 {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (max_iterations: {}): must be at least 1",
+                self.max_iterations
+            )));
+        }
+        if self.tolerance.is_nan() || self.tolerance < 0.0 {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (tolerance: {}): must not be negative",
+                self.tolerance
+            )));
+        }
+        Ok(CelebiOptions {
+            wu_bits: self.wu_bits,
+            max_iterations: self.max_iterations,
+            tolerance: self.tolerance,
+            seeding: self.seeding,
+            seed: self.seed,
+            point_provider: self.point_provider,
+            return_input_pixel_to_cluster_pixel: self.return_input_pixel_to_cluster_pixel,
+        })
     }
 }
 
@@ -23,7 +239,10 @@ impl Quantizer for QuantizerCelebi {
 mod test {
     use indexmap::IndexSet;
 
-    use crate::quantize::{celebi::QuantizerCelebi, Quantizer};
+    use crate::quantize::{
+        celebi::{CelebiOptions, QuantizerCelebi},
+        PointProviderCam16Ucs, Quantizer, QuantizerInput,
+    };
 
     const RED: u32 = 0xffff0000;
     const GREEN: u32 = 0xff00ff00;
@@ -32,8 +251,8 @@ mod test {
 
     #[test]
     fn one_red() {
-        let mut celebi = QuantizerCelebi;
-        let result = celebi.quantize(&vec![RED], MAX_COLORS);
+        let mut celebi = QuantizerCelebi::default();
+        let result = celebi.quantize(&[RED], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], RED);
@@ -41,8 +260,8 @@ mod test {
 
     #[test]
     fn one_green() {
-        let mut celebi = QuantizerCelebi;
-        let result = celebi.quantize(&vec![GREEN], MAX_COLORS);
+        let mut celebi = QuantizerCelebi::default();
+        let result = celebi.quantize(&[GREEN], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], GREEN);
@@ -50,8 +269,8 @@ mod test {
 
     #[test]
     fn one_blue() {
-        let mut celebi = QuantizerCelebi;
-        let result = celebi.quantize(&vec![BLUE], MAX_COLORS);
+        let mut celebi = QuantizerCelebi::default();
+        let result = celebi.quantize(&[BLUE], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], BLUE);
@@ -59,8 +278,8 @@ mod test {
 
     #[test]
     fn five_blue() {
-        let mut celebi = QuantizerCelebi;
-        let result = celebi.quantize(&vec![BLUE, BLUE, BLUE, BLUE, BLUE], MAX_COLORS);
+        let mut celebi = QuantizerCelebi::default();
+        let result = celebi.quantize(&[BLUE, BLUE, BLUE, BLUE, BLUE], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], BLUE);
@@ -68,8 +287,8 @@ mod test {
 
     #[test]
     fn one_red_one_green_one_blue() {
-        let mut celebi = QuantizerCelebi;
-        let result = celebi.quantize(&vec![RED, GREEN, BLUE], MAX_COLORS);
+        let mut celebi = QuantizerCelebi::default();
+        let result = celebi.quantize(&[RED, GREEN, BLUE], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
 
         let mut set = IndexSet::new();
@@ -85,8 +304,8 @@ mod test {
 
     #[test]
     fn two_red_three_green() {
-        let mut celebi = QuantizerCelebi;
-        let result = celebi.quantize(&vec![RED, RED, GREEN, GREEN, GREEN], MAX_COLORS);
+        let mut celebi = QuantizerCelebi::default();
+        let result = celebi.quantize(&[RED, RED, GREEN, GREEN, GREEN], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
 
         let mut set = IndexSet::new();
@@ -97,4 +316,72 @@ mod test {
         assert_eq!(colors[0], GREEN);
         assert_eq!(colors[1], RED);
     }
+
+    #[test]
+    fn cam16_ucs_point_provider() {
+        let options = CelebiOptions::builder()
+            .point_provider(PointProviderCam16Ucs)
+            .build()
+            .unwrap();
+        let mut celebi = QuantizerCelebi::new(options);
+        let result = celebi.quantize(&[RED, RED, GREEN, GREEN, GREEN], MAX_COLORS);
+        assert_eq!(result.color_to_count[&GREEN], 3);
+        assert_eq!(result.color_to_count[&RED], 2);
+    }
+
+    #[test]
+    fn options_are_validated() {
+        assert!(CelebiOptions::builder().wu_bits(3).build().is_err());
+        assert!(CelebiOptions::builder().wu_bits(8).build().is_err());
+        assert!(CelebiOptions::builder().wu_bits(7).build().is_ok());
+        assert!(CelebiOptions::builder().max_iterations(0).build().is_err());
+        assert!(CelebiOptions::builder().tolerance(-1.0).build().is_err());
+        assert!(CelebiOptions::builder()
+            .tolerance(f64::NAN)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn options_reach_wsmeans() {
+        let options = CelebiOptions::builder()
+            .max_iterations(1)
+            .return_input_pixel_to_cluster_pixel(true)
+            .build()
+            .unwrap();
+        let mut celebi = QuantizerCelebi::new(options);
+        let result = celebi.quantize(&[RED, RED, GREEN, GREEN, GREEN], MAX_COLORS);
+        assert_eq!(result.stats.unwrap().iterations, 1);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&RED], RED);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&GREEN], GREEN);
+    }
+
+    #[test]
+    fn weights_mask_and_alpha_threshold_reach_populations() {
+        let pixels = [RED, GREEN, 0x800000ff];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[2, 3, 5])
+            .unwrap()
+            .with_mask(&[true, false, true])
+            .unwrap()
+            .with_alpha_threshold(0x80);
+        let result = QuantizerCelebi::default().quantize_input(&input, MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 2);
+        assert_eq!(result.color_to_count[&RED], 2);
+        assert_eq!(result.color_to_count[&BLUE], 5);
+    }
+
+    #[test]
+    fn near_max_weights_saturate() {
+        let pixels = [0xffff0000, 0xfffe0000];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[u32::MAX, 5])
+            .unwrap();
+        let result = QuantizerCelebi::default().quantize_input(&input, 1);
+        assert_eq!(result.color_to_count.len(), 1);
+        assert_eq!(
+            result.color_to_count.values().copied().collect::<Vec<_>>(),
+            vec![u32::MAX]
+        );
+    }
 }
@@ -0,0 +1,272 @@
+use crate::error::ArgumentError;
+
+use super::pixel_buffer::PixelBuffer;
+
+/// How [`Downsampler`] reduces each block of pixels to one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SamplingMethod {
+    /// The mean of the block, with colors weighted by alpha.
+    #[default]
+    AreaAverage,
+    /// The pixel at the center of the block. Faster, but noisier.
+    Stride,
+    /// The mean of the block's opaque pixels, or transparent if it has none.
+    /// Suits color extraction, which ignores pixels that aren't opaque: a
+    /// block with a single translucent pixel, such as an anti-aliased edge,
+    /// is kept rather than averaged into a translucent pixel.
+    OpaqueAverage,
+}
+
+/// Shrinks an image by a whole factor until it has at most `max_pixels`
+/// pixels, so quantizers like [`QuantizerCelebi`](super::QuantizerCelebi)
+/// don't have to visit every pixel of a full-resolution photo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Downsampler {
+    method: SamplingMethod,
+    max_pixels: usize,
+}
+
+/// An image produced by [`Downsampler`], as ARGB rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownsampledImage {
+    pub pixels: Vec<u32>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for Downsampler {
+    fn default() -> Self {
+        Downsampler {
+            method: SamplingMethod::default(),
+            max_pixels: Self::DEFAULT_MAX_PIXELS,
+        }
+    }
+}
+
+impl Downsampler {
+    /// Upstream pipelines resize images to about 128px square before
+    /// quantizing.
+    pub const DEFAULT_MAX_PIXELS: usize = 128 * 128;
+
+    pub fn new(method: SamplingMethod, max_pixels: usize) -> Result<Downsampler, ArgumentError> {
+        if max_pixels == 0 {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (max_pixels: {}): must be positive",
+                max_pixels
+            )));
+        }
+        Ok(Downsampler { method, max_pixels })
+    }
+
+    pub fn method(&self) -> SamplingMethod {
+        self.method
+    }
+
+    pub fn max_pixels(&self) -> usize {
+        self.max_pixels
+    }
+
+    /// The side of the square blocks that are reduced to one pixel.
+    pub fn factor(&self, width: usize, height: usize) -> usize {
+        // The area is estimated in floating point, since it may not fit in
+        // usize.
+        let area = width as f64 * height as f64;
+        let mut factor = ((area / self.max_pixels as f64).sqrt() as usize).max(1);
+        while width
+            .div_ceil(factor)
+            .checked_mul(height.div_ceil(factor))
+            .is_none_or(|sampled_area| sampled_area > self.max_pixels)
+        {
+            factor += 1;
+        }
+        factor
+    }
+
+    /// Downsamples `pixels`, ARGB rows of `width` pixels.
+    pub fn downsample(
+        &self,
+        pixels: &[u32],
+        width: usize,
+        height: usize,
+    ) -> Result<DownsampledImage, ArgumentError> {
+        let area = width.checked_mul(height);
+        if area != Some(pixels.len()) {
+            return Err(ArgumentError::new(match area {
+                Some(area) => format!(
+                    "Invalid argument (pixels: {} pixels): must be {} for {}x{}",
+                    pixels.len(),
+                    area,
+                    width,
+                    height
+                ),
+                None => format!(
+                    "Invalid argument (height: {}): must be small enough for {}x{} pixels to fit in usize",
+                    height, width, height
+                ),
+            }));
+        }
+        Ok(self.downsample_with(width, height, |x, y| pixels[y * width + x]))
+    }
+
+    /// Downsamples `buffer`, decoding only the pixels that are sampled.
+    pub fn downsample_buffer(&self, buffer: &PixelBuffer) -> DownsampledImage {
+        self.downsample_with(buffer.width(), buffer.height(), |x, y| buffer.pixel(x, y))
+    }
+
+    fn downsample_with(
+        &self,
+        width: usize,
+        height: usize,
+        pixel_at: impl Fn(usize, usize) -> u32,
+    ) -> DownsampledImage {
+        let factor = self.factor(width, height);
+        let sampled_width = width.div_ceil(factor);
+        let sampled_height = height.div_ceil(factor);
+        let mut pixels = Vec::with_capacity(sampled_width * sampled_height);
+        for block_y in 0..sampled_height {
+            let y0 = block_y * factor;
+            let y1 = (y0 + factor).min(height);
+            for block_x in 0..sampled_width {
+                let x0 = block_x * factor;
+                let x1 = (x0 + factor).min(width);
+                pixels.push(match self.method {
+                    SamplingMethod::Stride => pixel_at((x0 + x1) / 2, (y0 + y1) / 2),
+                    SamplingMethod::AreaAverage => average(
+                        (y0..y1).flat_map(|y| (x0..x1).map(move |x| (x, y))),
+                        &pixel_at,
+                    ),
+                    SamplingMethod::OpaqueAverage => opaque_average(
+                        (y0..y1).flat_map(|y| (x0..x1).map(move |x| (x, y))),
+                        &pixel_at,
+                    ),
+                });
+            }
+        }
+        DownsampledImage {
+            pixels,
+            width: sampled_width,
+            height: sampled_height,
+        }
+    }
+}
+
+/// The mean of the pixels at `coordinates`, weighting colors by alpha so fully
+/// transparent pixels don't tint the result.
+fn average(
+    coordinates: impl Iterator<Item = (usize, usize)>,
+    pixel_at: &impl Fn(usize, usize) -> u32,
+) -> u32 {
+    let mut count = 0u64;
+    let mut alpha_sum = 0u64;
+    let mut color_sums = [0u64; 3];
+    for (x, y) in coordinates {
+        let [a, r, g, b] = pixel_at(x, y).to_be_bytes().map(u64::from);
+        count += 1;
+        alpha_sum += a;
+        for (sum, channel) in color_sums.iter_mut().zip([r, g, b]) {
+            *sum += channel * a;
+        }
+    }
+    if alpha_sum == 0 {
+        return 0;
+    }
+    let alpha = (alpha_sum + count / 2) / count;
+    let [r, g, b] = color_sums.map(|sum| (sum + alpha_sum / 2) / alpha_sum);
+    u32::from_be_bytes([alpha, r, g, b].map(|channel| channel as u8))
+}
+
+/// The mean of the opaque pixels at `coordinates`, or transparent black if
+/// none are opaque.
+fn opaque_average(
+    coordinates: impl Iterator<Item = (usize, usize)>,
+    pixel_at: &impl Fn(usize, usize) -> u32,
+) -> u32 {
+    let mut count = 0u64;
+    let mut color_sums = [0u64; 3];
+    for (x, y) in coordinates {
+        let [a, r, g, b] = pixel_at(x, y).to_be_bytes().map(u64::from);
+        if a < 255 {
+            continue;
+        }
+        count += 1;
+        for (sum, channel) in color_sums.iter_mut().zip([r, g, b]) {
+            *sum += channel;
+        }
+    }
+    if count == 0 {
+        return 0;
+    }
+    let [r, g, b] = color_sums.map(|sum| (sum + count / 2) / count);
+    u32::from_be_bytes([255, r, g, b].map(|channel| channel as u8))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::quantize::{PixelBuffer, PixelLayout};
+
+    use super::{Downsampler, SamplingMethod};
+
+    #[test]
+    fn fits_pixel_budget() {
+        let downsampler = Downsampler::default();
+        for (width, height) in [(4000, 3000), (128, 128), (129, 128), (10, 5000), (1, 1)] {
+            let pixels = vec![0xff102030; width * height];
+            let image = downsampler.downsample(&pixels, width, height).unwrap();
+            assert!(image.pixels.len() <= Downsampler::DEFAULT_MAX_PIXELS);
+            assert_eq!(image.pixels.len(), image.width * image.height);
+            assert!(image.pixels.iter().all(|&pixel| pixel == 0xff102030));
+        }
+        assert_eq!(downsampler.factor(128, 128), 1);
+        assert_eq!(downsampler.factor(4000, 3000), 28);
+        let factor = downsampler.factor(usize::MAX, usize::MAX);
+        assert!(
+            usize::MAX.div_ceil(factor).pow(2) <= Downsampler::DEFAULT_MAX_PIXELS,
+            "{factor}"
+        );
+    }
+
+    #[test]
+    fn area_average_and_stride() {
+        // A 2x2 image reduced to one pixel.
+        let pixels = [0xff000000, 0xffff0000, 0xff00ff00, 0x000000ff];
+        let average = Downsampler::new(SamplingMethod::AreaAverage, 1).unwrap();
+        assert_eq!(
+            average.downsample(&pixels, 2, 2).unwrap().pixels,
+            vec![0xbf555500]
+        );
+        let stride = Downsampler::new(SamplingMethod::Stride, 1).unwrap();
+        assert_eq!(
+            stride.downsample(&pixels, 2, 2).unwrap().pixels,
+            vec![0x000000ff]
+        );
+        let opaque = Downsampler::new(SamplingMethod::OpaqueAverage, 1).unwrap();
+        assert_eq!(
+            opaque.downsample(&pixels, 2, 2).unwrap().pixels,
+            vec![0xff555500]
+        );
+        assert_eq!(
+            opaque.downsample(&[0x80ff0000; 4], 2, 2).unwrap().pixels,
+            vec![0x00000000]
+        );
+    }
+
+    #[test]
+    fn downsamples_pixel_buffers() {
+        let bytes: Vec<u8> = (0..64u8).flat_map(|i| [i, i, i]).collect();
+        let buffer = PixelBuffer::packed(&bytes, 8, 8, PixelLayout::Rgb8).unwrap();
+        let downsampler = Downsampler::new(SamplingMethod::AreaAverage, 16).unwrap();
+        assert_eq!(
+            downsampler.downsample_buffer(&buffer),
+            downsampler.downsample(&buffer.to_argb(), 8, 8).unwrap()
+        );
+    }
+
+    #[test]
+    fn validates_arguments() {
+        assert!(Downsampler::new(SamplingMethod::Stride, 0).is_err());
+        assert!(Downsampler::default().downsample(&[0; 5], 2, 2).is_err());
+        assert!(Downsampler::default()
+            .downsample(&[0; 4], usize::MAX, 2)
+            .is_err());
+    }
+}
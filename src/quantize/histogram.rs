@@ -0,0 +1,174 @@
+use indexmap::IndexMap;
+
+use super::{
+    map::merge_counts, pixel_buffer::PixelBuffer, HistogramQuantizer, QuantizerInput,
+    QuantizerResult,
+};
+
+/// Builds the histogram that quantizers start from one chunk of pixels at a
+/// time, such as a row or a tile, so a huge image never has to be in memory
+/// at once. Memory grows with the number of distinct colors, not pixels.
+#[derive(Debug, Clone)]
+pub struct HistogramAccumulator {
+    color_to_count: IndexMap<u32, u32>,
+    alpha_threshold: u8,
+}
+
+impl Default for HistogramAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistogramAccumulator {
+    /// An empty histogram that, like [`QuantizerMap`](super::QuantizerMap),
+    /// only counts opaque pixels.
+    pub fn new() -> HistogramAccumulator {
+        HistogramAccumulator {
+            color_to_count: IndexMap::new(),
+            alpha_threshold: 255,
+        }
+    }
+
+    /// Counts pixels with alpha from `alpha_threshold` up, unless a chunk
+    /// passed to [`HistogramAccumulator::add_input`] sets its own threshold.
+    pub fn with_alpha_threshold(alpha_threshold: u8) -> HistogramAccumulator {
+        HistogramAccumulator {
+            alpha_threshold,
+            ..HistogramAccumulator::new()
+        }
+    }
+
+    pub fn add_pixels(&mut self, pixels: &[u32]) {
+        self.add_input(&QuantizerInput::new(pixels));
+    }
+
+    /// Adds the pixels of `buffer`, decoding a batch of rows at a time rather
+    /// than the whole buffer.
+    pub fn add_buffer(&mut self, buffer: &PixelBuffer) {
+        const BATCH_SIZE: usize = 1 << 16;
+        let mut batch = Vec::with_capacity(BATCH_SIZE.max(buffer.width()));
+        for y in 0..buffer.height() {
+            batch.extend(buffer.row(y));
+            if batch.len() >= BATCH_SIZE {
+                self.add_pixels(&batch);
+                batch.clear();
+            }
+        }
+        self.add_pixels(&batch);
+    }
+
+    /// Adds a chunk of weighted or masked pixels.
+    pub fn add_input(&mut self, input: &QuantizerInput) {
+        let counts = input.count_pixels(self.alpha_threshold, cfg!(feature = "parallel"));
+        merge_counts(&mut self.color_to_count, counts);
+    }
+
+    /// The population of each color so far, in order of first appearance.
+    pub fn color_to_count(&self) -> &IndexMap<u32, u32> {
+        &self.color_to_count
+    }
+
+    /// The histogram as [`QuantizerMap`](super::QuantizerMap) would report it.
+    pub fn finish(self) -> QuantizerResult {
+        QuantizerResult {
+            color_to_count: self.color_to_count,
+            input_pixel_to_cluster_pixel: IndexMap::new(),
+            stats: None,
+        }
+    }
+
+    /// Quantizes the histogram with `quantizer`.
+    pub fn finish_with<Q: HistogramQuantizer + ?Sized>(
+        &self,
+        quantizer: &mut Q,
+        max_colors: u32,
+    ) -> QuantizerResult {
+        quantizer.quantize_histogram(&self.color_to_count, max_colors)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::quantize::{
+        PixelBuffer, PixelLayout, Quantizer, QuantizerCelebi, QuantizerInput, QuantizerMap,
+        QuantizerWu,
+    };
+
+    use super::HistogramAccumulator;
+
+    fn pixels() -> Vec<u32> {
+        (0..10_000u32)
+            .map(|i| 0xff000000 | (i.wrapping_mul(2654435761) % 0x1000000))
+            .collect()
+    }
+
+    fn accumulate_rows(pixels: &[u32]) -> HistogramAccumulator {
+        let mut accumulator = HistogramAccumulator::new();
+        for row in pixels.chunks(100) {
+            accumulator.add_pixels(row);
+        }
+        accumulator
+    }
+
+    #[test]
+    fn chunks_match_whole_image() {
+        let mut pixels = pixels();
+        pixels.extend_from_slice(&[0x80ff0000, 0xff000001, 0xff000001]);
+        let whole = QuantizerMap.quantize(&pixels, 256).color_to_count;
+        let streamed = accumulate_rows(&pixels).finish().color_to_count;
+        assert!(whole.iter().eq(streamed.iter()));
+    }
+
+    #[test]
+    fn finishes_with_wu_and_celebi() {
+        let pixels = pixels();
+        let accumulator = accumulate_rows(&pixels);
+        assert_eq!(
+            accumulator
+                .finish_with(&mut QuantizerWu::new(), 16)
+                .color_to_count,
+            QuantizerWu::new().quantize(&pixels, 16).color_to_count
+        );
+        assert_eq!(
+            accumulator
+                .finish_with(&mut QuantizerCelebi::default(), 16)
+                .color_to_count,
+            QuantizerCelebi::default()
+                .quantize(&pixels, 16)
+                .color_to_count
+        );
+    }
+
+    #[test]
+    fn weighted_chunks_and_alpha_threshold() {
+        let mut accumulator = HistogramAccumulator::with_alpha_threshold(0);
+        accumulator.add_pixels(&[0x80ff0000, 0xff00ff00]);
+        let pixels = [0xff00ff00, 0x00ff0000];
+        let input = QuantizerInput::new(&pixels).with_weights(&[3, 2]).unwrap();
+        accumulator.add_input(&input);
+        assert_eq!(
+            accumulator.color_to_count().iter().collect::<Vec<_>>(),
+            vec![(&0x80ff0000, &1), (&0xff00ff00, &4), (&0x00ff0000, &2)]
+        );
+    }
+
+    #[test]
+    fn adds_pixel_buffers() {
+        let pixels = pixels();
+        let bytes: Vec<u8> = pixels
+            .iter()
+            .flat_map(|pixel| {
+                let [a, r, g, b] = pixel.to_be_bytes();
+                [b, g, r, a]
+            })
+            .collect();
+        let buffer = PixelBuffer::packed(&bytes, 100, 100, PixelLayout::Bgra8).unwrap();
+        let mut accumulator = HistogramAccumulator::new();
+        accumulator.add_buffer(&buffer);
+        assert!(accumulator
+            .color_to_count()
+            .iter()
+            .eq(accumulate_rows(&pixels).color_to_count().iter()));
+    }
+}
@@ -1,26 +1,131 @@
 use indexmap::IndexMap;
 
-use crate::utils::color_utils;
-
-use super::QuantizerResult;
+use super::{parallel::map_ranges, HistogramQuantizer, QuantizerInput, QuantizerResult};
 
 pub struct QuantizerMap;
 
 impl super::Quantizer for QuantizerMap {
-    fn quantize(&mut self, pixels: &[u32], _max_colors: u32) -> QuantizerResult {
-        let mut count_by_color = IndexMap::new();
-        for pixel in pixels {
-            let alpha = color_utils::alpha_from_argb(*pixel);
-
-            if alpha < 255 {
-                continue;
-            }
-
-            *count_by_color.entry(*pixel).or_insert(0) += 1;
-        }
+    fn quantize_input(&mut self, input: &QuantizerInput, _max_colors: u32) -> QuantizerResult {
+        let count_by_color = input.count_pixels(255, cfg!(feature = "parallel"));
         QuantizerResult {
             color_to_count: count_by_color,
             input_pixel_to_cluster_pixel: IndexMap::new(),
+            stats: None,
+        }
+    }
+}
+
+impl HistogramQuantizer for QuantizerMap {
+    fn quantize_histogram(
+        &mut self,
+        color_to_count: &IndexMap<u32, u32>,
+        _max_colors: u32,
+    ) -> QuantizerResult {
+        QuantizerResult {
+            color_to_count: color_to_count.clone(),
+            input_pixel_to_cluster_pixel: IndexMap::new(),
+            stats: None,
+        }
+    }
+}
+
+/// Sums the `weight` of each pixel, given its index and color, by color in
+/// order of first appearance. Colors whose pixels all weigh zero are left out.
+pub(crate) fn count_pixels(
+    pixels: &[u32],
+    parallel: bool,
+    weight: impl Fn(usize, u32) -> u32 + Sync,
+) -> IndexMap<u32, u32> {
+    let chunks = map_ranges(pixels.len(), 1 << 16, parallel, |range| {
+        let mut count_by_color = IndexMap::new();
+        for index in range {
+            let pixel = pixels[index];
+            let weight = weight(index, pixel);
+            if weight > 0 {
+                let count = count_by_color.entry(pixel).or_insert(0u32);
+                *count = count.saturating_add(weight);
+            }
         }
+        count_by_color
+    });
+    let mut chunks = chunks.into_iter();
+    let mut count_by_color = chunks.next().unwrap_or_default();
+    for chunk in chunks {
+        merge_counts(&mut count_by_color, chunk);
+    }
+    count_by_color
+}
+
+/// Adds the counts in `other` to `count_by_color`, appending new colors.
+pub(crate) fn merge_counts(count_by_color: &mut IndexMap<u32, u32>, other: IndexMap<u32, u32>) {
+    for (pixel, count) in other {
+        let total = count_by_color.entry(pixel).or_insert(0);
+        *total = total.saturating_add(count);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::quantize::{Quantizer, QuantizerInput};
+
+    use super::{count_pixels, QuantizerMap};
+
+    #[test]
+    fn counts_in_order_of_first_appearance() {
+        let pixels = [0xff0000ff, 0x80ff0000, 0xff00ff00, 0xff0000ff];
+        let counts = count_pixels(&pixels, false, |_, pixel| (pixel >> 24 == 0xff) as u32);
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            vec![(0xff0000ff, 2), (0xff00ff00, 1)]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_serial() {
+        let pixels: Vec<u32> = (0..1_000_000u32)
+            .map(|i| 0xff000000 | (i.wrapping_mul(2654435761) % 5000))
+            .collect();
+        let parallel = count_pixels(&pixels, true, |index, _| index as u32 % 3);
+        let serial = count_pixels(&pixels, false, |index, _| index as u32 % 3);
+        assert!(parallel.iter().eq(serial.iter()));
+    }
+
+    #[test]
+    fn weights_masks_and_alpha_threshold() {
+        let pixels = [0xff0000ff, 0x80ff0000, 0xff00ff00, 0xff0000ff, 0x10ffffff];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[3, 2, 1, 4, 9])
+            .unwrap()
+            .with_mask(&[true, true, false, true, true])
+            .unwrap()
+            .with_alpha_threshold(0x80);
+        let result = QuantizerMap.quantize_input(&input, 256);
+        assert_eq!(
+            result.color_to_count.into_iter().collect::<Vec<_>>(),
+            vec![(0xff0000ff, 7), (0x80ff0000, 2)]
+        );
+    }
+
+    #[test]
+    fn weights_and_mask_must_match_pixels() {
+        let pixels = [0xff0000ff, 0xff00ff00];
+        assert!(QuantizerInput::new(&pixels).with_weights(&[1]).is_err());
+        assert!(QuantizerInput::new(&pixels)
+            .with_mask(&[true, false, true])
+            .is_err());
+    }
+
+    #[test]
+    fn near_max_weights_saturate() {
+        let pixels = [0xffff0000, 0xfffe0000, 0xffff0000];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[u32::MAX, 5, 5])
+            .unwrap();
+        let result = QuantizerMap.quantize_input(&input, 1);
+        assert_eq!(
+            result.color_to_count.into_iter().collect::<Vec<_>>(),
+            vec![(0xffff0000, u32::MAX), (0xfffe0000, 5)]
+        );
     }
 }
@@ -0,0 +1,240 @@
+use indexmap::IndexMap;
+
+use crate::utils::color_utils;
+
+use super::{map::QuantizerMap, HistogramQuantizer, Quantizer, QuantizerInput, QuantizerResult};
+
+/// Median cut quantization. Repeatedly splits the box of colors with the
+/// widest channel range at its population-weighted median, until there are
+/// `max_colors` boxes or every box holds a single color.
+#[derive(Debug, Default)]
+pub struct QuantizerMedianCut {
+    /// Whether to map each distinct input color to the color of its box in
+    /// [`QuantizerResult::input_pixel_to_cluster_pixel`].
+    pub return_input_pixel_to_cluster_pixel: bool,
+}
+
+impl QuantizerMedianCut {
+    pub fn new() -> QuantizerMedianCut {
+        QuantizerMedianCut::default()
+    }
+}
+
+impl Quantizer for QuantizerMedianCut {
+    fn quantize_input(&mut self, input: &QuantizerInput, max_colors: u32) -> QuantizerResult {
+        let count_by_color = QuantizerMap
+            .quantize_input(input, max_colors)
+            .color_to_count;
+        self.quantize_histogram(&count_by_color, max_colors)
+    }
+}
+
+impl HistogramQuantizer for QuantizerMedianCut {
+    fn quantize_histogram(
+        &mut self,
+        count_by_color: &IndexMap<u32, u32>,
+        max_colors: u32,
+    ) -> QuantizerResult {
+        let mut entries: Vec<(u32, u32)> = count_by_color.iter().map(|(&c, &n)| (c, n)).collect();
+
+        let mut boxes = if entries.is_empty() {
+            vec![]
+        } else {
+            vec![ColorBox::new(&entries, 0, entries.len())]
+        };
+        while boxes.len() < max_colors as usize {
+            // Split the box with the widest range, preferring larger
+            // populations, then earlier boxes.
+            let Some((index, _)) = boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, color_box)| color_box.end - color_box.start > 1)
+                .max_by(|(i, a), (j, b)| {
+                    (a.widest_range(), a.population)
+                        .cmp(&(b.widest_range(), b.population))
+                        .then(j.cmp(i))
+                })
+            else {
+                break;
+            };
+            let color_box = boxes[index].clone();
+            let channel = color_box.widest_channel();
+            let slice = &mut entries[color_box.start..color_box.end];
+            slice.sort_by_key(|&(argb, _)| (channel_of(argb, channel), argb));
+
+            // The first index where the running population reaches half.
+            let mut running = 0;
+            let mut split = slice.len() - 1;
+            for (i, (_, count)) in slice.iter().enumerate() {
+                running += *count as u64;
+                if running * 2 >= color_box.population {
+                    split = i + 1;
+                    break;
+                }
+            }
+            let split = color_box.start + split.clamp(1, slice.len() - 1);
+            boxes[index] = ColorBox::new(&entries, color_box.start, split);
+            boxes.push(ColorBox::new(&entries, split, color_box.end));
+        }
+
+        let mut color_of_pixel = IndexMap::new();
+        for color_box in &boxes {
+            let color = color_box.average(&entries);
+            for (argb, _) in &entries[color_box.start..color_box.end] {
+                color_of_pixel.insert(*argb, color);
+            }
+        }
+
+        let mut color_to_count = IndexMap::new();
+        let mut input_pixel_to_cluster_pixel = IndexMap::new();
+        for (pixel, count) in count_by_color {
+            let color = color_of_pixel[pixel];
+            let population = color_to_count.entry(color).or_insert(0u32);
+            *population = population.saturating_add(*count);
+            if self.return_input_pixel_to_cluster_pixel {
+                input_pixel_to_cluster_pixel.insert(*pixel, color);
+            }
+        }
+
+        QuantizerResult {
+            color_to_count,
+            input_pixel_to_cluster_pixel,
+            stats: None,
+        }
+    }
+}
+
+/// The 8-bit red, green or blue channel of `argb`.
+fn channel_of(argb: u32, channel: usize) -> u32 {
+    match channel {
+        0 => color_utils::red_from_argb(argb),
+        1 => color_utils::green_from_argb(argb),
+        _ => color_utils::blue_from_argb(argb),
+    }
+}
+
+/// A range of the quantizer's entries, with its bounds and population.
+#[derive(Debug, Clone)]
+struct ColorBox {
+    start: usize,
+    end: usize,
+    min: [u32; 3],
+    max: [u32; 3],
+    population: u64,
+}
+
+impl ColorBox {
+    fn new(entries: &[(u32, u32)], start: usize, end: usize) -> ColorBox {
+        let mut min = [255; 3];
+        let mut max = [0; 3];
+        let mut population = 0;
+        for (argb, count) in &entries[start..end] {
+            for channel in 0..3 {
+                let value = channel_of(*argb, channel);
+                min[channel] = min[channel].min(value);
+                max[channel] = max[channel].max(value);
+            }
+            population += *count as u64;
+        }
+        ColorBox {
+            start,
+            end,
+            min,
+            max,
+            population,
+        }
+    }
+
+    fn widest_channel(&self) -> usize {
+        (0..3)
+            .max_by(|&a, &b| {
+                (self.max[a] - self.min[a])
+                    .cmp(&(self.max[b] - self.min[b]))
+                    .then(b.cmp(&a))
+            })
+            .unwrap()
+    }
+
+    fn widest_range(&self) -> u32 {
+        let channel = self.widest_channel();
+        self.max[channel] - self.min[channel]
+    }
+
+    /// The population-weighted mean color of the box.
+    fn average(&self, entries: &[(u32, u32)]) -> u32 {
+        let mut sums = [0u64; 3];
+        for (argb, count) in &entries[self.start..self.end] {
+            for (channel, sum) in sums.iter_mut().enumerate() {
+                *sum += channel_of(*argb, channel) as u64 * *count as u64;
+            }
+        }
+        let [r, g, b] = sums.map(|sum| ((sum + self.population / 2) / self.population) as u32);
+        color_utils::argb_from_rgb(r, g, b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::quantize::{Quantizer, QuantizerInput};
+
+    use super::QuantizerMedianCut;
+
+    const RED: u32 = 0xffff0000;
+    const GREEN: u32 = 0xff00ff00;
+    const BLUE: u32 = 0xff0000ff;
+    const MAX_COLORS: u32 = 256;
+
+    #[test]
+    fn one_red() {
+        let result = QuantizerMedianCut::new().quantize(&[RED], MAX_COLORS);
+        let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
+        assert_eq!(colors, vec![RED]);
+    }
+
+    #[test]
+    fn two_red_three_green() {
+        let result =
+            QuantizerMedianCut::new().quantize(&[RED, RED, GREEN, GREEN, GREEN], MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 2);
+        assert_eq!(result.color_to_count[&RED], 2);
+        assert_eq!(result.color_to_count[&GREEN], 3);
+    }
+
+    #[test]
+    fn respects_max_colors() {
+        let pixels: Vec<u32> = (0..4096).map(|i| 0xff000000 | (i * 0x0f0b07)).collect();
+        let result = QuantizerMedianCut::new().quantize(&pixels, 16);
+        assert!(result.color_to_count.len() <= 16);
+        assert_eq!(result.color_to_count.values().sum::<u32>(), 4096);
+    }
+
+    #[test]
+    fn maps_input_pixels() {
+        let mut median_cut = QuantizerMedianCut {
+            return_input_pixel_to_cluster_pixel: true,
+        };
+        let result = median_cut.quantize(&[RED, 0xfffe0000, GREEN, BLUE], 3);
+        let red_cluster = result.input_pixel_to_cluster_pixel[&RED];
+        assert_eq!(
+            result.input_pixel_to_cluster_pixel[&0xfffe0000],
+            red_cluster
+        );
+        assert_eq!(result.color_to_count[&red_cluster], 2);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&GREEN], GREEN);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&BLUE], BLUE);
+    }
+
+    #[test]
+    fn near_max_weights_saturate() {
+        let pixels = [0xffff0000, 0xfffe0000];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[u32::MAX, 5])
+            .unwrap();
+        let result = QuantizerMedianCut::default().quantize_input(&input, 1);
+        assert_eq!(result.color_to_count.len(), 1);
+        assert_eq!(
+            result.color_to_count.values().copied().collect::<Vec<_>>(),
+            vec![u32::MAX]
+        );
+    }
+}
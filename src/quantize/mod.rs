@@ -1,21 +1,176 @@
+use std::time::Duration;
+
 use indexmap::IndexMap;
 
+use crate::{error::ArgumentError, utils::color_utils};
+
 pub mod celebi;
+pub mod downsample;
+pub mod histogram;
 pub mod map;
+pub mod median_cut;
+pub mod octree;
+mod parallel;
+pub mod pixel_buffer;
 pub mod point_provider;
+pub mod point_provider_cam16_ucs;
 pub mod point_provider_lab;
+pub mod point_provider_oklab;
 pub mod wsmeans;
 pub mod wu;
 
 pub use self::{
-    celebi::QuantizerCelebi, map::QuantizerMap, wsmeans::QuantizerWsmeans, wu::QuantizerWu,
+    celebi::{CelebiOptions, CelebiOptionsBuilder, QuantizerCelebi},
+    downsample::{DownsampledImage, Downsampler, SamplingMethod},
+    histogram::HistogramAccumulator,
+    map::QuantizerMap,
+    median_cut::QuantizerMedianCut,
+    octree::QuantizerOctree,
+    pixel_buffer::{PixelBuffer, PixelLayout},
+    point_provider::PointProvider,
+    point_provider_cam16_ucs::PointProviderCam16Ucs,
+    point_provider_lab::PointProviderLab,
+    point_provider_oklab::PointProviderOklab,
+    wsmeans::QuantizerWsmeans,
+    wu::QuantizerWu,
 };
 
 pub trait Quantizer {
-    fn quantize(&mut self, pixels: &[u32], max_colors: u32) -> QuantizerResult;
+    fn quantize(&mut self, pixels: &[u32], max_colors: u32) -> QuantizerResult {
+        self.quantize_input(&QuantizerInput::new(pixels), max_colors)
+    }
+
+    /// Quantizes weighted or masked pixels. Each population in the result is
+    /// the sum of its pixels' weights.
+    fn quantize_input(&mut self, input: &QuantizerInput, max_colors: u32) -> QuantizerResult;
+}
+
+/// A quantizer that can start from a histogram of colors and their
+/// populations, such as one built by [`HistogramAccumulator`], instead of
+/// from pixels.
+pub trait HistogramQuantizer: Quantizer {
+    fn quantize_histogram(
+        &mut self,
+        color_to_count: &IndexMap<u32, u32>,
+        max_colors: u32,
+    ) -> QuantizerResult;
+}
+
+/// Pixels to quantize, with optional per-pixel weights, a mask and an alpha
+/// threshold.
+#[derive(Debug, Clone, Copy)]
+pub struct QuantizerInput<'a> {
+    pixels: &'a [u32],
+    weights: Option<&'a [u32]>,
+    mask: Option<&'a [bool]>,
+    alpha_threshold: Option<u8>,
+}
+
+impl<'a> QuantizerInput<'a> {
+    /// Every pixel, weighing 1.
+    pub fn new(pixels: &'a [u32]) -> QuantizerInput<'a> {
+        QuantizerInput {
+            pixels,
+            weights: None,
+            mask: None,
+            alpha_threshold: None,
+        }
+    }
+
+    /// Weighs each pixel by the entry of `weights` at the same index. A weight
+    /// of 0 ignores the pixel.
+    pub fn with_weights(self, weights: &'a [u32]) -> Result<QuantizerInput<'a>, ArgumentError> {
+        if weights.len() != self.pixels.len() {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (weights: {} entries): must match the {} pixels",
+                weights.len(),
+                self.pixels.len()
+            )));
+        }
+        Ok(QuantizerInput {
+            weights: Some(weights),
+            ..self
+        })
+    }
+
+    /// Ignores each pixel whose entry of `mask` at the same index is false.
+    pub fn with_mask(self, mask: &'a [bool]) -> Result<QuantizerInput<'a>, ArgumentError> {
+        if mask.len() != self.pixels.len() {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (mask: {} entries): must match the {} pixels",
+                mask.len(),
+                self.pixels.len()
+            )));
+        }
+        Ok(QuantizerInput {
+            mask: Some(mask),
+            ..self
+        })
+    }
+
+    /// Ignores pixels with alpha below `alpha_threshold`. Without a threshold,
+    /// Wsmeans uses every pixel and the other quantizers only opaque ones.
+    pub fn with_alpha_threshold(self, alpha_threshold: u8) -> QuantizerInput<'a> {
+        QuantizerInput {
+            alpha_threshold: Some(alpha_threshold),
+            ..self
+        }
+    }
+
+    pub fn pixels(&self) -> &'a [u32] {
+        self.pixels
+    }
+
+    pub fn weights(&self) -> Option<&'a [u32]> {
+        self.weights
+    }
+
+    pub fn mask(&self) -> Option<&'a [bool]> {
+        self.mask
+    }
+
+    pub fn alpha_threshold(&self) -> Option<u8> {
+        self.alpha_threshold
+    }
+
+    /// Sums the weights of the pixels by color, in order of first appearance,
+    /// with `default_alpha_threshold` applying unless the input sets one.
+    pub(crate) fn count_pixels(
+        &self,
+        default_alpha_threshold: u8,
+        parallel: bool,
+    ) -> IndexMap<u32, u32> {
+        let alpha_threshold = self.alpha_threshold.unwrap_or(default_alpha_threshold) as u32;
+        map::count_pixels(self.pixels, parallel, |index, pixel| {
+            if color_utils::alpha_from_argb(pixel) < alpha_threshold
+                || self.mask.is_some_and(|mask| !mask[index])
+            {
+                return 0;
+            }
+            self.weights.map_or(1, |weights| weights[index])
+        })
+    }
 }
 
 pub struct QuantizerResult {
     pub color_to_count: IndexMap<u32, u32>,
     pub input_pixel_to_cluster_pixel: IndexMap<u32, u32>,
+    /// Diagnostics from quantizers that iterate, `None` for the others.
+    pub stats: Option<QuantizerStats>,
+}
+
+/// Diagnostics describing how an iterative quantizer converged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizerStats {
+    /// The number of iterations run.
+    pub iterations: usize,
+    /// The number of distinct colors that changed cluster, per iteration.
+    pub points_moved: Vec<usize>,
+    /// The number of clusters left without any pixels.
+    pub empty_clusters: usize,
+    /// Time spent quantizing.
+    pub elapsed: Duration,
+    /// The sum, over all pixels, of the point provider's distance from each
+    /// pixel to its cluster.
+    pub within_cluster_error: f64,
 }
@@ -0,0 +1,215 @@
+use indexmap::IndexMap;
+
+use crate::utils::color_utils;
+
+use super::{map::QuantizerMap, HistogramQuantizer, Quantizer, QuantizerInput, QuantizerResult};
+
+/// Octree quantization. Inserts every distinct color into an octree eight
+/// levels deep, one level per bit of each channel, then folds the children of
+/// the deepest, least populated nodes into their parent until at most
+/// `max_colors` leaves remain.
+#[derive(Debug, Default)]
+pub struct QuantizerOctree {
+    /// Whether to map each distinct input color to the color of its leaf in
+    /// [`QuantizerResult::input_pixel_to_cluster_pixel`].
+    pub return_input_pixel_to_cluster_pixel: bool,
+}
+
+impl QuantizerOctree {
+    const DEPTH: usize = 8;
+
+    pub fn new() -> QuantizerOctree {
+        QuantizerOctree::default()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: [Option<usize>; 8],
+    sums: [u64; 3],
+    population: u64,
+    is_leaf: bool,
+}
+
+impl Node {
+    fn color(&self) -> u32 {
+        let [r, g, b] = self
+            .sums
+            .map(|sum| ((sum + self.population / 2) / self.population) as u32);
+        color_utils::argb_from_rgb(r, g, b)
+    }
+}
+
+/// The index of the child holding `argb` at `level`, where the root is level
+/// zero and reads the top bit of each channel.
+fn child_index(argb: u32, level: usize) -> usize {
+    let shift = 7 - level;
+    let r = (color_utils::red_from_argb(argb) >> shift) & 1;
+    let g = (color_utils::green_from_argb(argb) >> shift) & 1;
+    let b = (color_utils::blue_from_argb(argb) >> shift) & 1;
+    ((r << 2) | (g << 1) | b) as usize
+}
+
+impl Quantizer for QuantizerOctree {
+    fn quantize_input(&mut self, input: &QuantizerInput, max_colors: u32) -> QuantizerResult {
+        let count_by_color = QuantizerMap
+            .quantize_input(input, max_colors)
+            .color_to_count;
+        self.quantize_histogram(&count_by_color, max_colors)
+    }
+}
+
+impl HistogramQuantizer for QuantizerOctree {
+    fn quantize_histogram(
+        &mut self,
+        count_by_color: &IndexMap<u32, u32>,
+        max_colors: u32,
+    ) -> QuantizerResult {
+        let mut nodes = vec![Node::default()];
+        // Internal nodes by level, in order of creation.
+        let mut levels: Vec<Vec<usize>> = vec![vec![]; Self::DEPTH];
+        levels[0].push(0);
+        let mut leaf_count = 0;
+        for (&argb, &count) in count_by_color {
+            let rgb = [
+                color_utils::red_from_argb(argb),
+                color_utils::green_from_argb(argb),
+                color_utils::blue_from_argb(argb),
+            ];
+            let mut index = 0;
+            for level in 0..=Self::DEPTH {
+                let node = &mut nodes[index];
+                node.population += count as u64;
+                for (sum, value) in node.sums.iter_mut().zip(rgb) {
+                    *sum += value as u64 * count as u64;
+                }
+                if level == Self::DEPTH {
+                    if !node.is_leaf {
+                        node.is_leaf = true;
+                        leaf_count += 1;
+                    }
+                    break;
+                }
+                let child = child_index(argb, level);
+                index = match nodes[index].children[child] {
+                    Some(child) => child,
+                    None => {
+                        let child_node = nodes.len();
+                        nodes.push(Node::default());
+                        nodes[index].children[child] = Some(child_node);
+                        if level + 1 < Self::DEPTH {
+                            levels[level + 1].push(child_node);
+                        }
+                        child_node
+                    }
+                };
+            }
+        }
+
+        // A node's population is fixed once every color is inserted, so each
+        // level can be ordered once. Levels are reduced deepest first, which
+        // keeps the children of every node being reduced already leaves.
+        let max_leaves = (max_colors as usize).max(1);
+        'reduce: for level in (0..Self::DEPTH).rev() {
+            let mut candidates = std::mem::take(&mut levels[level]);
+            candidates.sort_by_key(|&index| nodes[index].population);
+            for index in candidates {
+                if leaf_count <= max_leaves {
+                    break 'reduce;
+                }
+                let children = nodes[index].children.iter().flatten().count();
+                nodes[index].children = [None; 8];
+                nodes[index].is_leaf = true;
+                leaf_count -= children - 1;
+            }
+        }
+
+        let mut color_to_count = IndexMap::new();
+        let mut input_pixel_to_cluster_pixel = IndexMap::new();
+        for (&pixel, &count) in count_by_color {
+            let mut index = 0;
+            let mut level = 0;
+            while !nodes[index].is_leaf {
+                index = nodes[index].children[child_index(pixel, level)].unwrap();
+                level += 1;
+            }
+            let color = nodes[index].color();
+            let population = color_to_count.entry(color).or_insert(0u32);
+            *population = population.saturating_add(count);
+            if self.return_input_pixel_to_cluster_pixel {
+                input_pixel_to_cluster_pixel.insert(pixel, color);
+            }
+        }
+
+        QuantizerResult {
+            color_to_count,
+            input_pixel_to_cluster_pixel,
+            stats: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::quantize::{Quantizer, QuantizerInput};
+
+    use super::QuantizerOctree;
+
+    const RED: u32 = 0xffff0000;
+    const GREEN: u32 = 0xff00ff00;
+    const BLUE: u32 = 0xff0000ff;
+    const MAX_COLORS: u32 = 256;
+
+    #[test]
+    fn one_red() {
+        let result = QuantizerOctree::new().quantize(&[RED], MAX_COLORS);
+        let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
+        assert_eq!(colors, vec![RED]);
+    }
+
+    #[test]
+    fn two_red_three_green() {
+        let result = QuantizerOctree::new().quantize(&[RED, RED, GREEN, GREEN, GREEN], MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 2);
+        assert_eq!(result.color_to_count[&RED], 2);
+        assert_eq!(result.color_to_count[&GREEN], 3);
+    }
+
+    #[test]
+    fn respects_max_colors() {
+        let pixels: Vec<u32> = (0..4096).map(|i| 0xff000000 | (i * 0x0f0b07)).collect();
+        let result = QuantizerOctree::new().quantize(&pixels, 16);
+        assert!(result.color_to_count.len() <= 16);
+        assert_eq!(result.color_to_count.values().sum::<u32>(), 4096);
+    }
+
+    #[test]
+    fn maps_input_pixels() {
+        let mut octree = QuantizerOctree {
+            return_input_pixel_to_cluster_pixel: true,
+        };
+        let result = octree.quantize(&[RED, 0xfffe0000, GREEN, BLUE], 3);
+        let red_cluster = result.input_pixel_to_cluster_pixel[&RED];
+        assert_eq!(
+            result.input_pixel_to_cluster_pixel[&0xfffe0000],
+            red_cluster
+        );
+        assert_eq!(result.color_to_count[&red_cluster], 2);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&GREEN], GREEN);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&BLUE], BLUE);
+    }
+
+    #[test]
+    fn near_max_weights_saturate() {
+        let pixels = [0xffff0000, 0xfffe0000];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[u32::MAX, 5])
+            .unwrap();
+        let result = QuantizerOctree::default().quantize_input(&input, 1);
+        assert_eq!(result.color_to_count.len(), 1);
+        assert_eq!(
+            result.color_to_count.values().copied().collect::<Vec<_>>(),
+            vec![u32::MAX]
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use std::ops::Range;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Splits `0..len` into consecutive ranges of at least `min_chunk_len`, maps
+/// each with `f` and returns the results in order.
+///
+/// Ranges are mapped on rayon's thread pool when `parallel` is set and the
+/// `parallel` feature is enabled, and as a single range otherwise. Callers
+/// combine the results in order, so the output doesn't depend on how the work
+/// was split.
+pub(crate) fn map_ranges<R, F>(len: usize, min_chunk_len: usize, parallel: bool, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(Range<usize>) -> R + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if parallel && len > min_chunk_len {
+        let chunk_count = (rayon::current_num_threads() * 4).min(len.div_ceil(min_chunk_len));
+        let chunk_len = len.div_ceil(chunk_count);
+        return (0..len.div_ceil(chunk_len))
+            .into_par_iter()
+            .map(|chunk| f(chunk * chunk_len..((chunk + 1) * chunk_len).min(len)))
+            .collect();
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = (min_chunk_len, parallel);
+    vec![f(0..len)]
+}
@@ -0,0 +1,232 @@
+use crate::error::ArgumentError;
+
+/// The byte order of each pixel in a [`PixelBuffer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelLayout {
+    /// Red, green, blue and alpha bytes, as in canvas `ImageData`.
+    Rgba8,
+    /// Blue, green, red and alpha bytes, as in many software framebuffers.
+    Bgra8,
+    /// Red, green and blue bytes, read as opaque.
+    Rgb8,
+    /// Red, green, blue and alpha bytes, with the colors multiplied by alpha.
+    Rgba8Premultiplied,
+}
+
+impl PixelLayout {
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelLayout::Rgb8 => 3,
+            _ => 4,
+        }
+    }
+}
+
+/// A view of pixels stored as bytes, read as ARGB without copying the buffer.
+/// Rows may be padded: each starts `stride` bytes after the previous one.
+#[derive(Debug, Clone, Copy)]
+pub struct PixelBuffer<'a> {
+    bytes: &'a [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    layout: PixelLayout,
+}
+
+impl<'a> PixelBuffer<'a> {
+    /// Returns an error if rows are shorter than `width` pixels or `bytes`
+    /// can't hold `height` rows. The last row needn't be padded.
+    pub fn new(
+        bytes: &'a [u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+        layout: PixelLayout,
+    ) -> Result<PixelBuffer<'a>, ArgumentError> {
+        let row_length = width.checked_mul(layout.bytes_per_pixel()).ok_or_else(|| {
+            ArgumentError::new(format!(
+                "Invalid argument (width: {}): must be small enough for a row to fit in usize",
+                width
+            ))
+        })?;
+        if stride < row_length {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (stride: {}): must be at least {} bytes for {} pixels",
+                stride, row_length, width
+            )));
+        }
+        let required = match height.checked_sub(1) {
+            None => 0,
+            Some(last_row) => stride
+                .checked_mul(last_row)
+                .and_then(|offset| offset.checked_add(row_length))
+                .ok_or_else(|| {
+                    ArgumentError::new(format!(
+                        "Invalid argument (height: {}): must be small enough for the buffer to fit in usize",
+                        height
+                    ))
+                })?,
+        };
+        if bytes.len() < required {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (bytes: {} bytes): must be at least {} bytes",
+                bytes.len(),
+                required
+            )));
+        }
+        Ok(PixelBuffer {
+            bytes,
+            width,
+            height,
+            stride,
+            layout,
+        })
+    }
+
+    /// A buffer whose rows are not padded.
+    pub fn packed(
+        bytes: &'a [u8],
+        width: usize,
+        height: usize,
+        layout: PixelLayout,
+    ) -> Result<PixelBuffer<'a>, ArgumentError> {
+        // An overflowing row length is reported by PixelBuffer::new.
+        let stride = width.saturating_mul(layout.bytes_per_pixel());
+        PixelBuffer::new(bytes, width, height, stride, layout)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn layout(&self) -> PixelLayout {
+        self.layout
+    }
+
+    /// The pixel at column `x` of row `y`, as ARGB.
+    pub fn pixel(&self, x: usize, y: usize) -> u32 {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside {}x{}",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        let bytes_per_pixel = self.layout.bytes_per_pixel();
+        let start = y * self.stride + x * bytes_per_pixel;
+        argb_from_bytes(&self.bytes[start..start + bytes_per_pixel], self.layout)
+    }
+
+    /// The pixels of row `y`, as ARGB.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = u32> + 'a {
+        let bytes_per_pixel = self.layout.bytes_per_pixel();
+        let start = y * self.stride;
+        let layout = self.layout;
+        self.bytes[start..start + self.width * bytes_per_pixel]
+            .chunks_exact(bytes_per_pixel)
+            .map(move |pixel| argb_from_bytes(pixel, layout))
+    }
+
+    /// Every pixel, row by row, as ARGB.
+    pub fn pixels(&self) -> impl Iterator<Item = u32> + 'a {
+        let buffer = *self;
+        (0..self.height).flat_map(move |y| buffer.row(y))
+    }
+
+    /// Copies the pixels into ARGB, for APIs that need a slice.
+    pub fn to_argb(&self) -> Vec<u32> {
+        self.pixels().collect()
+    }
+}
+
+fn argb_from_bytes(pixel: &[u8], layout: PixelLayout) -> u32 {
+    let [r, g, b, a] = match layout {
+        PixelLayout::Rgba8 => [pixel[0], pixel[1], pixel[2], pixel[3]],
+        PixelLayout::Bgra8 => [pixel[2], pixel[1], pixel[0], pixel[3]],
+        PixelLayout::Rgb8 => [pixel[0], pixel[1], pixel[2], 255],
+        PixelLayout::Rgba8Premultiplied => {
+            let a = pixel[3];
+            let unpremultiply = |c: u8| match a {
+                0 => 0,
+                _ => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+            };
+            [
+                unpremultiply(pixel[0]),
+                unpremultiply(pixel[1]),
+                unpremultiply(pixel[2]),
+                a,
+            ]
+        }
+    };
+    u32::from_be_bytes([a, r, g, b])
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PixelBuffer, PixelLayout};
+
+    #[test]
+    fn reads_each_layout() {
+        let rgba = [0x12, 0x34, 0x56, 0xff];
+        let bgra = [0x56, 0x34, 0x12, 0xff];
+        let rgb = [0x12, 0x34, 0x56];
+        for (bytes, layout) in [
+            (&rgba[..], PixelLayout::Rgba8),
+            (&bgra[..], PixelLayout::Bgra8),
+            (&rgb[..], PixelLayout::Rgb8),
+            (&rgba[..], PixelLayout::Rgba8Premultiplied),
+        ] {
+            let buffer = PixelBuffer::packed(bytes, 1, 1, layout).unwrap();
+            assert_eq!(buffer.to_argb(), vec![0xff123456], "{layout:?}");
+        }
+    }
+
+    #[test]
+    fn unpremultiplies() {
+        let bytes = [0x40, 0x20, 0x00, 0x80, 0xff, 0xff, 0xff, 0x00];
+        let buffer = PixelBuffer::packed(&bytes, 2, 1, PixelLayout::Rgba8Premultiplied).unwrap();
+        assert_eq!(buffer.to_argb(), vec![0x80804000, 0x00000000]);
+    }
+
+    #[test]
+    fn skips_row_padding() {
+        // Two rows of two RGB pixels, each padded to 8 bytes.
+        let bytes = [
+            1, 2, 3, 4, 5, 6, 0xee, 0xee, //
+            7, 8, 9, 10, 11, 12,
+        ];
+        let buffer = PixelBuffer::new(&bytes, 2, 2, 8, PixelLayout::Rgb8).unwrap();
+        assert_eq!(
+            buffer.to_argb(),
+            vec![0xff010203, 0xff040506, 0xff070809, 0xff0a0b0c]
+        );
+    }
+
+    #[test]
+    fn validates_dimensions() {
+        let bytes = [0; 16];
+        assert!(PixelBuffer::new(&bytes, 2, 2, 7, PixelLayout::Rgba8).is_err());
+        assert!(PixelBuffer::new(&bytes, 2, 3, 8, PixelLayout::Rgba8).is_err());
+        assert!(PixelBuffer::new(&bytes, 2, 2, 8, PixelLayout::Rgba8).is_ok());
+        assert!(PixelBuffer::new(&bytes, 2, 0, 8, PixelLayout::Rgba8).is_ok());
+    }
+
+    #[test]
+    fn rejects_dimensions_that_overflow() {
+        let bytes = [0; 16];
+        let huge = usize::MAX / 2;
+        assert!(PixelBuffer::new(&bytes, huge, 1, usize::MAX, PixelLayout::Rgba8).is_err());
+        assert!(PixelBuffer::packed(&bytes, huge, 1, PixelLayout::Rgba8).is_err());
+        assert!(PixelBuffer::new(&bytes, 1, huge, 8, PixelLayout::Rgba8).is_err());
+        assert!(PixelBuffer::new(&bytes, 1, 2, usize::MAX, PixelLayout::Rgba8).is_err());
+    }
+}
@@ -1,4 +1,12 @@
-pub trait PointProvider {
+/// Converts colors to and from the points a quantizer clusters.
+///
+/// `distance` must be the squared Euclidean distance between points, or at
+/// least the square of a metric: [`super::QuantizerWsmeans`] relies on the
+/// triangle inequality to skip distance calculations.
+///
+/// Providers are `Sync` so quantizers can share them across threads.
+#[allow(clippy::wrong_self_convention)]
+pub trait PointProvider: Sync {
     fn from_int(&self, argb: u32) -> [f64; 3];
     fn to_int(&self, point: &[f64]) -> u32;
     fn distance(&self, a: &[f64], b: &[f64]) -> f64;
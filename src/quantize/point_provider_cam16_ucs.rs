@@ -0,0 +1,40 @@
+use crate::hct::Cam16;
+
+use super::point_provider::PointProvider;
+
+/// Clusters colors in CAM16-UCS, the space [`Cam16::distance`] and HCT
+/// measure colors in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PointProviderCam16Ucs;
+
+impl PointProvider for PointProviderCam16Ucs {
+    fn from_int(&self, argb: u32) -> [f64; 3] {
+        let cam = Cam16::from_int(argb);
+        [cam.jstar, cam.astar, cam.bstar]
+    }
+    fn to_int(&self, point: &[f64]) -> u32 {
+        Cam16::from_ucs(point[0], point[1], point[2]).viewed_in_srgb()
+    }
+    fn distance(&self, one: &[f64], two: &[f64]) -> f64 {
+        let d_j = one[0] - two[0];
+        let d_a = one[1] - two[1];
+        let d_b = one[2] - two[2];
+        // Squared Euclidean distance. Cam16::distance additionally applies a
+        // monotonic power curve, which doesn't change the relative ordering.
+        d_j * d_j + d_a * d_a + d_b * d_b
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::quantize::{point_provider::PointProvider, PointProviderCam16Ucs};
+
+    #[test]
+    fn round_trips() {
+        let provider = PointProviderCam16Ucs;
+        for argb in [0xffff0000, 0xff00ff00, 0xff0000ff, 0xff6750a4, 0xffffffff] {
+            let point = provider.from_int(argb);
+            assert_eq!(provider.to_int(&point), argb);
+        }
+    }
+}
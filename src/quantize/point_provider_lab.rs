@@ -2,6 +2,7 @@ use crate::utils::color_utils;
 
 use super::point_provider::PointProvider;
 
+#[derive(Debug, Clone, Copy, Default)]
 pub struct PointProviderLab;
 
 impl PointProvider for PointProviderLab {
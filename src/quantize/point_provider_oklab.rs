@@ -0,0 +1,23 @@
+use crate::utils::color_utils;
+
+use super::point_provider::PointProvider;
+
+/// Clusters colors in Oklab.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PointProviderOklab;
+
+impl PointProvider for PointProviderOklab {
+    fn from_int(&self, argb: u32) -> [f64; 3] {
+        color_utils::oklab_from_argb(argb)
+    }
+    fn to_int(&self, oklab: &[f64]) -> u32 {
+        color_utils::argb_from_oklab(oklab[0], oklab[1], oklab[2])
+    }
+    fn distance(&self, one: &[f64], two: &[f64]) -> f64 {
+        let d_l = one[0] - two[0];
+        let d_a = one[1] - two[1];
+        let d_b = one[2] - two[2];
+        // Squared, like PointProviderLab; the ordering is what matters.
+        d_l * d_l + d_a * d_a + d_b * d_b
+    }
+}
@@ -1,78 +1,168 @@
-use std::{cmp::Ordering, iter::zip, time::Instant};
+use std::{iter::zip, time::Instant};
 
 use indexmap::IndexMap;
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, Rng, RngExt, SeedableRng};
 
 use super::{
-    point_provider::PointProvider, point_provider_lab::PointProviderLab, Quantizer, QuantizerResult,
+    parallel::map_ranges, point_provider::PointProvider, point_provider_lab::PointProviderLab,
+    HistogramQuantizer, Quantizer, QuantizerInput, QuantizerResult, QuantizerStats,
 };
 
-#[derive(Debug, Clone)]
-struct DistanceAndIndex {
-    distance: f64,
-    index: usize,
+/// Bounds are only trusted to skip a point when they clear it by this factor.
+const BOUND_MARGIN: f64 = 1.0 - 1e-9;
+
+/// Hamerly's bounds, in distance rather than squared distance: `upper` on each
+/// point's distance to its cluster, and `lower` on its distance to every other
+/// cluster.
+struct Bounds {
+    upper: Vec<f64>,
+    lower: Vec<f64>,
 }
 
-impl DistanceAndIndex {
-    pub fn new(distance: f64, index: usize) -> DistanceAndIndex {
-        DistanceAndIndex { distance, index }
+impl Bounds {
+    /// Loosens the bounds after each cluster `i` moved by the squared distance
+    /// `shifts[i]`.
+    fn update(&mut self, cluster_indices: &[usize], shifts: &[f64]) {
+        let shifts: Vec<f64> = shifts.iter().map(|shift| shift.sqrt()).collect();
+        let mut largest = 0;
+        for (i, shift) in shifts.iter().enumerate() {
+            if *shift > shifts[largest] {
+                largest = i;
+            }
+        }
+        let second_largest = (0..shifts.len())
+            .filter(|&i| i != largest)
+            .map(|i| shifts[i])
+            .fold(0.0, f64::max);
+        for (i, &cluster_index) in cluster_indices.iter().enumerate() {
+            self.upper[i] += shifts[cluster_index];
+            self.lower[i] -= if cluster_index == largest {
+                second_largest
+            } else {
+                shifts[largest]
+            };
+        }
     }
 }
 
-impl PartialEq for DistanceAndIndex {
-    fn eq(&self, other: &Self) -> bool {
-        self.distance.eq(&other.distance)
-    }
+/// Cluster assignments and bounds for a range of points.
+struct Assignment {
+    cluster_indices: Vec<usize>,
+    upper: Vec<f64>,
+    lower: Vec<f64>,
+    points_moved: usize,
 }
 
-impl PartialOrd for DistanceAndIndex {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.distance.partial_cmp(&other.distance)
-    }
+/// How [`QuantizerWsmeans`] picks starting clusters beyond
+/// `starting_clusters`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Seeding {
+    /// Distinct input colors, chosen uniformly at random.
+    #[default]
+    Random,
+    /// Input colors chosen with probability proportional to their population
+    /// times their distance to the nearest cluster chosen so far.
+    KMeansPlusPlus,
 }
 
-pub struct QuantizerWsmeans {
-    pub debug: bool,
+pub struct QuantizerWsmeans<P: PointProvider = PointProviderLab> {
     pub starting_clusters: Vec<u32>,
-    pub point_provider: PointProviderLab,
+    pub point_provider: P,
     pub max_iterations: i32,
+    /// Stops iterating once no cluster moves at least this far, measured with
+    /// `point_provider`'s distance. 0 iterates until no points move.
+    pub tolerance: f64,
+    /// How to choose starting clusters beyond `starting_clusters`.
+    pub seeding: Seeding,
+    /// Seeds the choice of additional starting clusters, unless
+    /// [`QuantizerWsmeans::quantize_with_rng`] is used.
+    pub seed: u64,
     pub return_input_pixel_to_cluster_pixel: bool,
 }
 
 impl Default for QuantizerWsmeans {
     fn default() -> QuantizerWsmeans {
-        Self {
-            debug: true,
+        QuantizerWsmeans::with_point_provider(PointProviderLab)
+    }
+}
+
+impl<P: PointProvider> QuantizerWsmeans<P> {
+    pub const DEFAULT_SEED: u64 = 0x42688;
+
+    /// Default settings, clustering in the space of `point_provider`.
+    pub fn with_point_provider(point_provider: P) -> QuantizerWsmeans<P> {
+        QuantizerWsmeans {
             starting_clusters: vec![],
-            point_provider: PointProviderLab,
+            point_provider,
             max_iterations: 5,
+            tolerance: 0.0,
+            seeding: Seeding::Random,
+            seed: Self::DEFAULT_SEED,
             return_input_pixel_to_cluster_pixel: false,
         }
     }
 }
-impl Quantizer for QuantizerWsmeans {
-    fn quantize(&mut self, input_pixels: &[u32], max_colors: u32) -> QuantizerResult {
-        let mut random = StdRng::seed_from_u64(0x42688);
-        let mut pixel_to_count = IndexMap::new();
-        let mut points = vec![];
-        let mut pixels = vec![];
-        let mut point_count = 0;
-        for input_pixel in input_pixels {
-            *pixel_to_count.entry(input_pixel).or_insert(0) += 1;
-            let pixel_count = pixel_to_count[input_pixel];
-            if pixel_count == 1 {
-                point_count += 1;
-                points.push(self.point_provider.from_int(*input_pixel));
-                pixels.push(input_pixel);
-            }
-        }
 
-        let mut counts = vec![0; point_count];
-        for i in 0..point_count {
-            let pixel = pixels[i];
-            let count = *pixel_to_count.get(&pixel).unwrap();
-            counts[i] = count;
-        }
+impl<P: PointProvider> Quantizer for QuantizerWsmeans<P> {
+    fn quantize_input(&mut self, input: &QuantizerInput, max_colors: u32) -> QuantizerResult {
+        let mut random = StdRng::seed_from_u64(self.seed);
+        self.quantize_with_rng(input, max_colors, &mut random)
+    }
+}
+
+impl<P: PointProvider> HistogramQuantizer for QuantizerWsmeans<P> {
+    fn quantize_histogram(
+        &mut self,
+        pixel_to_count: &IndexMap<u32, u32>,
+        max_colors: u32,
+    ) -> QuantizerResult {
+        let mut random = StdRng::seed_from_u64(self.seed);
+        self.quantize_impl(
+            pixel_to_count,
+            max_colors,
+            &mut random,
+            true,
+            cfg!(feature = "parallel"),
+        )
+    }
+}
+
+impl<P: PointProvider> QuantizerWsmeans<P> {
+    /// Like [`Quantizer::quantize_input`], drawing starting clusters from
+    /// `random` instead of an RNG seeded with `seed`.
+    pub fn quantize_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        input: &QuantizerInput,
+        max_colors: u32,
+        random: &mut R,
+    ) -> QuantizerResult {
+        let parallel = cfg!(feature = "parallel");
+        let pixel_to_count = input.count_pixels(0, parallel);
+        self.quantize_impl(&pixel_to_count, max_colors, random, true, parallel)
+    }
+
+    /// Assigns points to clusters exhaustively unless `accelerated`, in which
+    /// case Hamerly's bounds skip points that can't move, and splits work
+    /// across threads if `parallel`. All combinations give identical results.
+    fn quantize_impl<R: Rng + ?Sized>(
+        &mut self,
+        pixel_to_count: &IndexMap<u32, u32>,
+        max_colors: u32,
+        random: &mut R,
+        accelerated: bool,
+        parallel: bool,
+    ) -> QuantizerResult {
+        let stopwatch = Instant::now();
+        let pixels: Vec<u32> = pixel_to_count.keys().copied().collect();
+        let counts: Vec<u32> = pixel_to_count.values().copied().collect();
+        let point_count = pixels.len();
+        let points: Vec<[f64; 3]> = map_ranges(point_count, 1 << 12, parallel, |range| {
+            pixels[range]
+                .iter()
+                .map(|pixel| self.point_provider.from_int(*pixel))
+                .collect::<Vec<_>>()
+        })
+        .concat();
 
         let cluster_count = max_colors.min(point_count as u32) as usize;
 
@@ -82,7 +172,15 @@ impl Quantizer for QuantizerWsmeans {
             .map(|e| self.point_provider.from_int(*e))
             .collect();
         let additional_clusters_needed = cluster_count - clusters.len();
-        if additional_clusters_needed > 0 {
+        if additional_clusters_needed > 0 && self.seeding == Seeding::KMeansPlusPlus {
+            self.seed_kmeans_plus_plus(
+                &points,
+                &counts,
+                &mut clusters,
+                additional_clusters_needed,
+                random,
+            );
+        } else if additional_clusters_needed > 0 {
             let mut indices = vec![];
             for _ in 0..additional_clusters_needed {
                 // Use existing points rather than generating random centroids.
@@ -101,9 +199,9 @@ impl Quantizer for QuantizerWsmeans {
                 // Rather than generate random centroids, we'll pick centroids that
                 // are actual pixels in the image, and avoid duplicating centroids.
 
-                let mut index = random.gen_range(0..points.len());
+                let mut index = random.random_range(0..points.len());
                 while indices.contains(&index) {
-                    index = random.gen_range(0..points.len());
+                    index = random.random_range(0..points.len());
                 }
                 indices.push(index);
             }
@@ -112,129 +210,117 @@ impl Quantizer for QuantizerWsmeans {
                 clusters.push(points[index]);
             }
         }
-        if self.debug {
-            println!(
-                "have {} starting clusters, {} points",
-                clusters.len(),
-                points.len()
-            );
-        }
-
         let mut cluster_indices: Vec<usize> = (0..point_count)
             .map(|index| index % cluster_count)
             .collect();
-        let mut index_matrix = vec![vec![0; cluster_count]; cluster_count];
-
-        let mut distance_to_index_matrix: Vec<Vec<DistanceAndIndex>> = (0..cluster_count)
-            .map(|_| {
-                (0..cluster_count)
-                    .map(|index| DistanceAndIndex::new(0.0, index))
-                    .collect()
-            })
-            .collect();
-
-        let mut pixel_count_sums = vec![0; cluster_count];
+        let mut bounds = Bounds {
+            upper: vec![f64::INFINITY; point_count],
+            lower: vec![0.0; point_count],
+        };
+
+        let mut pixel_count_sums = vec![0u64; cluster_count];
+        let mut iterations = 0;
+        let mut points_moved_per_iteration = vec![];
         for iteration in 0..self.max_iterations {
-            if self.debug {
+            iterations += 1;
+            let mut points_moved = 0;
+
+            // For each cluster, the other clusters sorted by distance from it.
+            let mut neighbors: Vec<Vec<(f64, usize)>> = vec![vec![]; cluster_count];
+            if accelerated {
                 for i in 0..cluster_count {
-                    pixel_count_sums[i] = 0;
-                }
-                for i in 0..point_count {
-                    let cluster_index = cluster_indices[i];
-                    let count = counts[i];
-                    pixel_count_sums[cluster_index] += count;
-                }
-                let mut empty_clusters = 0;
-                for cluster in 0..cluster_count {
-                    if pixel_count_sums[cluster] == 0 {
-                        empty_clusters += 1;
+                    for j in i + 1..cluster_count {
+                        let distance = self.point_provider.distance(&clusters[i], &clusters[j]);
+                        neighbors[i].push((distance, j));
+                        neighbors[j].push((distance, i));
                     }
                 }
-                println!(
-                    "starting iteration {}; {} clusters are empty of {}",
-                    iteration + 1,
-                    empty_clusters,
-                    cluster_count
-                );
-            }
-
-            let mut points_moved = 0;
-            for i in 0..cluster_count {
-                for j in i + 1..cluster_count {
-                    let distance = self.point_provider.distance(&clusters[i], &clusters[j]);
-                    distance_to_index_matrix[j][i].distance = distance;
-                    distance_to_index_matrix[j][i].index = i;
-                    distance_to_index_matrix[i][j].distance = distance;
-                    distance_to_index_matrix[i][j].index = j;
-                }
-                //distance_to_index_matrix[i].sort();
-                for j in 0..cluster_count {
-                    index_matrix[i][j] = distance_to_index_matrix[i][j].index;
+                for row in &mut neighbors {
+                    row.sort_by(|a, b| a.partial_cmp(b).unwrap());
                 }
             }
 
-            for i in 0..point_count {
-                let point = points[i];
-                let previous_cluster_index = cluster_indices[i];
-                let previous_cluster = &clusters[previous_cluster_index][..];
-                let previous_distance = self.point_provider.distance(&point, previous_cluster);
-                let mut minimum_distance = previous_distance;
-                let mut new_cluster_index: isize = -1;
-                for j in 0..cluster_count {
-                    if distance_to_index_matrix[previous_cluster_index][j].distance
-                        >= 4.0 * previous_distance
-                    {
-                        continue;
-                    }
-                    let distance = self.point_provider.distance(&point, &clusters[j]);
-                    if distance < minimum_distance {
-                        minimum_distance = distance;
-                        new_cluster_index = j as isize;
-                    }
-                }
-                if new_cluster_index != -1 {
-                    points_moved += 1;
-                    cluster_indices[i] = new_cluster_index as usize;
-                }
+            let chunks = map_ranges(point_count, 1 << 12, parallel, |range| {
+                let assignment = Assignment {
+                    cluster_indices: cluster_indices[range.clone()].to_vec(),
+                    upper: bounds.upper[range.clone()].to_vec(),
+                    lower: bounds.lower[range.clone()].to_vec(),
+                    points_moved: 0,
+                };
+                self.assign_points(
+                    &points[range],
+                    &clusters,
+                    &neighbors,
+                    assignment,
+                    accelerated,
+                )
+            });
+            let mut start = 0;
+            for chunk in chunks {
+                let end = start + chunk.cluster_indices.len();
+                cluster_indices[start..end].copy_from_slice(&chunk.cluster_indices);
+                bounds.upper[start..end].copy_from_slice(&chunk.upper);
+                bounds.lower[start..end].copy_from_slice(&chunk.lower);
+                points_moved += chunk.points_moved;
+                start = end;
             }
 
+            points_moved_per_iteration.push(points_moved);
             if points_moved == 0 && iteration > 0 {
-                if self.debug {
-                    println!("terminated after {} k-means iterations", iteration);
-                }
                 break;
             }
 
-            if self.debug {
-                println!("iteration {} moved {}", iteration + 1, points_moved);
+            // Sum each cluster's points in point order, so the sums don't
+            // depend on how clusters are split across threads.
+            let mut members = vec![vec![]; cluster_count];
+            for (i, &cluster_index) in cluster_indices.iter().enumerate() {
+                members[cluster_index].push(i);
             }
-            let mut component_a_sums = vec![0.0; cluster_count];
-            let mut component_b_sums = vec![0.0; cluster_count];
-            let mut component_c_sums = vec![0.0; cluster_count];
+            let sums: Vec<(u64, [f64; 3])> = map_ranges(cluster_count, 1, parallel, |range| {
+                members[range]
+                    .iter()
+                    .map(|members| {
+                        let mut count_sum = 0;
+                        let mut component_sums = [0.0; 3];
+                        for &i in members {
+                            let point = points[i];
+                            let count = counts[i];
+                            count_sum += count as u64;
+                            component_sums[0] += point[0] * count as f64;
+                            component_sums[1] += point[1] * count as f64;
+                            component_sums[2] += point[2] * count as f64;
+                        }
+                        (count_sum, component_sums)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .concat();
 
+            let mut max_shift: f64 = 0.0;
+            let mut shifts = vec![0.0; cluster_count];
             for i in 0..cluster_count {
-                pixel_count_sums[i] = 0;
-            }
-            for i in 0..point_count {
-                let cluster_index = cluster_indices[i];
-                let point = points[i];
-                let count = counts[i];
-                pixel_count_sums[cluster_index] += count;
-                component_a_sums[cluster_index] += point[0] * count as f64;
-                component_b_sums[cluster_index] += point[1] * count as f64;
-                component_c_sums[cluster_index] += point[2] * count as f64;
-            }
-            for i in 0..cluster_count {
-                let count = pixel_count_sums[i];
+                let (count, [component_a_sum, component_b_sum, component_c_sum]) = sums[i];
+                pixel_count_sums[i] = count;
                 if count == 0 {
+                    shifts[i] = self.point_provider.distance(&clusters[i], &[0.0, 0.0, 0.0]);
                     clusters[i] = [0.0, 0.0, 0.0];
                     continue;
                 }
-                let a = component_a_sums[i] / count as f64;
-                let b = component_b_sums[i] / count as f64;
-                let c = component_c_sums[i] / count as f64;
+                let a = component_a_sum / count as f64;
+                let b = component_b_sum / count as f64;
+                let c = component_c_sum / count as f64;
+                shifts[i] = self.point_provider.distance(&clusters[i], &[a, b, c]);
+                max_shift = max_shift.max(shifts[i]);
                 clusters[i] = [a, b, c];
             }
+
+            if accelerated {
+                bounds.update(&cluster_indices, &shifts);
+            }
+
+            if max_shift < self.tolerance {
+                break;
+            }
         }
 
         let mut cluster_argbs = vec![];
@@ -251,46 +337,202 @@ impl Quantizer for QuantizerWsmeans {
             }
 
             cluster_argbs.push(possible_new_cluster);
-            cluster_populations.push(count);
-        }
-        if self.debug {
-            println!(
-                "kmeans finished and generated {} clusters; {} were requested",
-                cluster_argbs.len(),
-                cluster_count
-            );
+            cluster_populations.push(u32::try_from(count).unwrap_or(u32::MAX));
         }
 
         let mut input_pixel_to_cluster_pixel = IndexMap::new();
         if self.return_input_pixel_to_cluster_pixel {
-            let stopwatch = Instant::now();
             for i in 0..pixels.len() {
                 let input_pixel = pixels[i];
                 let cluster_index = cluster_indices[i];
                 let cluster = clusters[cluster_index];
                 let cluster_pixel = self.point_provider.to_int(&cluster);
-                input_pixel_to_cluster_pixel.insert(*input_pixel, cluster_pixel);
-            }
-            if self.debug {
-                println!(
-                    "took {} ms to create input to cluster map",
-                    stopwatch.elapsed().as_millis()
-                );
+                input_pixel_to_cluster_pixel.insert(input_pixel, cluster_pixel);
             }
         }
 
+        let within_cluster_error = (0..point_count)
+            .map(|i| {
+                let cluster = &clusters[cluster_indices[i]];
+                self.point_provider.distance(&points[i], cluster) * counts[i] as f64
+            })
+            .sum();
+        let stats = QuantizerStats {
+            iterations,
+            points_moved: points_moved_per_iteration,
+            empty_clusters: pixel_count_sums.iter().filter(|&&sum| sum == 0).count(),
+            elapsed: stopwatch.elapsed(),
+            within_cluster_error,
+        };
+
         QuantizerResult {
-            color_to_count: zip(cluster_argbs, cluster_populations)
-                .map(|(key, value)| (key, value))
-                .collect(),
+            color_to_count: zip(cluster_argbs, cluster_populations).collect(),
             input_pixel_to_cluster_pixel,
+            stats: Some(stats),
+        }
+    }
+
+    /// Moves each point of `assignment` to its nearest cluster, updating its
+    /// bounds if `accelerated`.
+    fn assign_points(
+        &self,
+        points: &[[f64; 3]],
+        clusters: &[[f64; 3]],
+        neighbors: &[Vec<(f64, usize)>],
+        mut assignment: Assignment,
+        accelerated: bool,
+    ) -> Assignment {
+        for (i, &point) in points.iter().enumerate() {
+            let previous_cluster_index = assignment.cluster_indices[i];
+            let previous_cluster = &clusters[previous_cluster_index][..];
+            let new_cluster_index = if accelerated {
+                // A point closer to its cluster than half the distance to
+                // the nearest other cluster, or than the lower bound,
+                // can't move. Skipping must never change the result, so
+                // leave a margin for rounding in the bounds.
+                let half_nearest = neighbors[previous_cluster_index]
+                    .first()
+                    .map_or(f64::INFINITY, |(distance, _)| distance.sqrt() / 2.0);
+                let bound = half_nearest.max(assignment.lower[i]) * BOUND_MARGIN;
+                if assignment.upper[i] < bound {
+                    continue;
+                }
+                let previous_distance = self.point_provider.distance(&point, previous_cluster);
+                assignment.upper[i] = previous_distance.sqrt();
+                if assignment.upper[i] < bound {
+                    continue;
+                }
+
+                // Neighboring pixels tend to share a cluster, so start
+                // from the previous point's cluster if it is closer.
+                let mut minimum_distance = previous_distance;
+                let mut new_cluster_index = None;
+                let mut second_distance = f64::INFINITY;
+                let mut consider = |j: usize, distance: f64| {
+                    if distance < minimum_distance
+                        || (distance == minimum_distance
+                            && new_cluster_index.is_some_and(|index| j < index))
+                    {
+                        second_distance = minimum_distance;
+                        minimum_distance = distance;
+                        new_cluster_index = Some(j);
+                    } else {
+                        second_distance = second_distance.min(distance);
+                    }
+                };
+                let mut anchor = previous_cluster_index;
+                let mut anchor_distance = previous_distance;
+                let last_cluster_index = assignment.cluster_indices[i.saturating_sub(1)];
+                if last_cluster_index != previous_cluster_index {
+                    let distance = self
+                        .point_provider
+                        .distance(&point, &clusters[last_cluster_index]);
+                    consider(last_cluster_index, distance);
+                    if distance < anchor_distance {
+                        anchor = last_cluster_index;
+                        anchor_distance = distance;
+                    }
+                }
+
+                // Scan the clusters nearest the anchor first. Once a
+                // cluster is at least twice as far from the anchor as the
+                // point is, it and every cluster after it are at least as
+                // far from the point as the anchor.
+                let mut unscanned_lower = f64::INFINITY;
+                for &(cluster_distance, j) in &neighbors[anchor] {
+                    if cluster_distance * BOUND_MARGIN >= 4.0 * anchor_distance {
+                        unscanned_lower = cluster_distance.sqrt() - anchor_distance.sqrt();
+                        break;
+                    }
+                    if j == previous_cluster_index || j == last_cluster_index {
+                        continue;
+                    }
+                    consider(j, self.point_provider.distance(&point, &clusters[j]));
+                }
+                assignment.upper[i] = minimum_distance.sqrt();
+                assignment.lower[i] = second_distance.sqrt().min(unscanned_lower);
+                new_cluster_index
+            } else {
+                let previous_distance = self.point_provider.distance(&point, previous_cluster);
+                let mut minimum_distance = previous_distance;
+                let mut new_cluster_index = None;
+                for (j, cluster) in clusters.iter().enumerate() {
+                    let distance = self.point_provider.distance(&point, cluster);
+                    if distance < minimum_distance {
+                        minimum_distance = distance;
+                        new_cluster_index = Some(j);
+                    }
+                }
+                new_cluster_index
+            };
+            if let Some(new_cluster_index) = new_cluster_index {
+                assignment.points_moved += 1;
+                assignment.cluster_indices[i] = new_cluster_index;
+            }
+        }
+        assignment
+    }
+
+    /// Appends `needed` clusters to `clusters` using k-means++.
+    fn seed_kmeans_plus_plus<R: Rng + ?Sized>(
+        &self,
+        points: &[[f64; 3]],
+        counts: &[u32],
+        clusters: &mut Vec<[f64; 3]>,
+        needed: usize,
+        random: &mut R,
+    ) {
+        // Each point's distance to its nearest cluster, or 1 while there are
+        // no clusters so the first is chosen by population alone.
+        let mut distances: Vec<f64> = points
+            .iter()
+            .map(|point| {
+                if clusters.is_empty() {
+                    return 1.0;
+                }
+                clusters
+                    .iter()
+                    .map(|cluster| self.point_provider.distance(point, cluster))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        for _ in 0..needed {
+            let weights: Vec<f64> = zip(&distances, counts)
+                .map(|(distance, count)| distance * *count as f64)
+                .collect();
+            let total: f64 = weights.iter().sum();
+            let index = if total > 0.0 {
+                let mut target = random.random_range(0.0..total);
+                let mut chosen = weights.iter().rposition(|&weight| weight > 0.0).unwrap();
+                for (i, weight) in weights.iter().enumerate() {
+                    if target < *weight {
+                        chosen = i;
+                        break;
+                    }
+                    target -= weight;
+                }
+                chosen
+            } else {
+                // Every point coincides with a cluster.
+                random.random_range(0..points.len())
+            };
+            let cluster = points[index];
+            clusters.push(cluster);
+            for (distance, point) in zip(&mut distances, points) {
+                *distance = distance.min(self.point_provider.distance(point, &cluster));
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::quantize::{wsmeans::QuantizerWsmeans, Quantizer};
+    use rand::{rngs::StdRng, RngExt, SeedableRng};
+
+    use crate::quantize::{
+        wsmeans::{QuantizerWsmeans, Seeding},
+        PointProviderCam16Ucs, PointProviderOklab, Quantizer, QuantizerInput,
+    };
 
     const RED: u32 = 0xffff0000;
     const GREEN: u32 = 0xff00ff00;
@@ -299,7 +541,7 @@ mod test {
 
     #[test]
     fn one_random() {
-        let result = QuantizerWsmeans::default().quantize(&vec![0xff141216], MAX_COLORS);
+        let result = QuantizerWsmeans::default().quantize(&[0xff141216], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], 0xff141216);
@@ -307,7 +549,7 @@ mod test {
 
     #[test]
     fn one_red() {
-        let result = QuantizerWsmeans::default().quantize(&vec![RED], MAX_COLORS);
+        let result = QuantizerWsmeans::default().quantize(&[RED], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], RED);
@@ -315,7 +557,7 @@ mod test {
 
     #[test]
     fn one_green() {
-        let result = QuantizerWsmeans::default().quantize(&vec![GREEN], MAX_COLORS);
+        let result = QuantizerWsmeans::default().quantize(&[GREEN], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], GREEN);
@@ -323,7 +565,7 @@ mod test {
 
     #[test]
     fn one_blue() {
-        let result = QuantizerWsmeans::default().quantize(&vec![BLUE], MAX_COLORS);
+        let result = QuantizerWsmeans::default().quantize(&[BLUE], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], BLUE);
@@ -332,9 +574,222 @@ mod test {
     #[test]
     fn five_blue() {
         let result =
-            QuantizerWsmeans::default().quantize(&vec![BLUE, BLUE, BLUE, BLUE, BLUE], MAX_COLORS);
+            QuantizerWsmeans::default().quantize(&[BLUE, BLUE, BLUE, BLUE, BLUE], MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
         assert_eq!(colors.len(), 1);
         assert_eq!(colors[0], BLUE);
     }
+
+    #[test]
+    fn point_providers_are_swappable() {
+        let pixels = [RED, RED, GREEN, BLUE, BLUE, BLUE];
+        let result = QuantizerWsmeans::with_point_provider(PointProviderCam16Ucs)
+            .quantize(&pixels, MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 3);
+        assert_eq!(result.color_to_count[&BLUE], 3);
+        let result =
+            QuantizerWsmeans::with_point_provider(PointProviderOklab).quantize(&pixels, MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 3);
+        assert_eq!(result.color_to_count[&RED], 2);
+    }
+
+    #[test]
+    fn reports_stats() {
+        let result =
+            QuantizerWsmeans::default().quantize(&[RED, RED, GREEN, BLUE, BLUE, BLUE], MAX_COLORS);
+        let stats = result.stats.unwrap();
+        assert!(stats.iterations >= 1);
+        assert_eq!(stats.points_moved.len(), stats.iterations);
+        assert_eq!(stats.empty_clusters, 0);
+        assert_eq!(stats.within_cluster_error, 0.0);
+    }
+
+    #[test]
+    fn tolerance_stops_early() {
+        let pixels: Vec<u32> = (0..64).map(|i| 0xff000000 | (i * 0x030507)).collect();
+        let exhaustive = QuantizerWsmeans {
+            max_iterations: 100,
+            ..QuantizerWsmeans::default()
+        }
+        .quantize(&pixels, 8);
+        let tolerant = QuantizerWsmeans {
+            max_iterations: 100,
+            tolerance: f64::INFINITY,
+            ..QuantizerWsmeans::default()
+        }
+        .quantize(&pixels, 8);
+        assert_eq!(tolerant.stats.unwrap().iterations, 1);
+        assert!(exhaustive.stats.unwrap().iterations > 1);
+    }
+
+    #[test]
+    fn seed_changes_starting_clusters() {
+        let pixels: Vec<u32> = (0..64).map(|i| 0xff000000 | (i * 0x030507)).collect();
+        let quantize = |seed| {
+            QuantizerWsmeans {
+                seed,
+                max_iterations: 1,
+                ..QuantizerWsmeans::default()
+            }
+            .quantize(&pixels, 8)
+            .color_to_count
+        };
+        assert_eq!(quantize(1), quantize(1));
+        assert_ne!(quantize(1), quantize(2));
+    }
+
+    #[test]
+    fn kmeans_plus_plus_spreads_clusters() {
+        // Two tight groups; k-means++ should start one cluster in each.
+        let mut pixels = vec![];
+        for i in 0..8 {
+            pixels.push(0xff000000 | i);
+            pixels.push(0xffffff00 | i);
+        }
+        let result = QuantizerWsmeans {
+            seeding: Seeding::KMeansPlusPlus,
+            max_iterations: 10,
+            ..QuantizerWsmeans::default()
+        }
+        .quantize(&pixels, 2);
+        let populations: Vec<u32> = result.color_to_count.values().copied().collect();
+        assert_eq!(populations, vec![8, 8]);
+    }
+
+    #[test]
+    fn caller_supplied_rng() {
+        let pixels: Vec<u32> = (0..64).map(|i| 0xff000000 | (i * 0x030507)).collect();
+        let mut wsmeans = QuantizerWsmeans {
+            seed: 7,
+            ..QuantizerWsmeans::default()
+        };
+        let seeded = wsmeans.quantize(&pixels, 8).color_to_count;
+        let supplied = wsmeans
+            .quantize_with_rng(
+                &QuantizerInput::new(&pixels),
+                8,
+                &mut StdRng::seed_from_u64(7),
+            )
+            .color_to_count;
+        assert_eq!(seeded, supplied);
+    }
+
+    #[test]
+    fn accelerated_assignment_matches_exhaustive() {
+        let mut random = StdRng::seed_from_u64(1);
+        let noise: Vec<u32> = (0..2000)
+            .map(|_| 0xff000000 | random.random_range(0..0x1000000))
+            .collect();
+        let gradient: Vec<u32> = (0..2000)
+            .map(|i| 0xff000000 | ((i % 50) * 5) << 16 | ((i / 50) * 6) << 8 | ((i % 7) * 30))
+            .collect();
+        for (pixels, seeding) in [
+            (&noise, Seeding::Random),
+            (&noise, Seeding::KMeansPlusPlus),
+            (&gradient, Seeding::Random),
+        ] {
+            let pixel_to_count = QuantizerInput::new(pixels).count_pixels(0, false);
+            for max_colors in [1, 16, 128] {
+                let mut wsmeans = QuantizerWsmeans {
+                    seeding,
+                    max_iterations: 20,
+                    return_input_pixel_to_cluster_pixel: true,
+                    ..QuantizerWsmeans::default()
+                };
+                let accelerated = wsmeans.quantize_impl(
+                    &pixel_to_count,
+                    max_colors,
+                    &mut StdRng::seed_from_u64(2),
+                    true,
+                    false,
+                );
+                let exhaustive = wsmeans.quantize_impl(
+                    &pixel_to_count,
+                    max_colors,
+                    &mut StdRng::seed_from_u64(2),
+                    false,
+                    false,
+                );
+                assert_eq!(accelerated.color_to_count, exhaustive.color_to_count);
+                assert_eq!(
+                    accelerated.input_pixel_to_cluster_pixel,
+                    exhaustive.input_pixel_to_cluster_pixel
+                );
+                let accelerated = accelerated.stats.unwrap();
+                let exhaustive = exhaustive.stats.unwrap();
+                assert_eq!(accelerated.points_moved, exhaustive.points_moved);
+                assert_eq!(
+                    accelerated.within_cluster_error,
+                    exhaustive.within_cluster_error
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_serial() {
+        let mut random = StdRng::seed_from_u64(3);
+        let pixels: Vec<u32> = (0..100_000)
+            .map(|_| 0xff000000 | random.random_range(0..0x1000000))
+            .collect();
+        let mut wsmeans = QuantizerWsmeans {
+            max_iterations: 10,
+            return_input_pixel_to_cluster_pixel: true,
+            ..QuantizerWsmeans::default()
+        };
+        let pixel_to_count = QuantizerInput::new(&pixels).count_pixels(0, true);
+        let parallel = wsmeans.quantize_impl(
+            &pixel_to_count,
+            64,
+            &mut StdRng::seed_from_u64(4),
+            true,
+            true,
+        );
+        let serial = wsmeans.quantize_impl(
+            &pixel_to_count,
+            64,
+            &mut StdRng::seed_from_u64(4),
+            true,
+            false,
+        );
+        assert_eq!(parallel.color_to_count, serial.color_to_count);
+        assert_eq!(
+            parallel.input_pixel_to_cluster_pixel,
+            serial.input_pixel_to_cluster_pixel
+        );
+        let parallel = parallel.stats.unwrap();
+        let serial = serial.stats.unwrap();
+        assert_eq!(parallel.points_moved, serial.points_moved);
+        assert_eq!(
+            parallel.within_cluster_error.to_bits(),
+            serial.within_cluster_error.to_bits()
+        );
+    }
+
+    #[test]
+    fn weights_become_populations() {
+        let pixels = [RED, GREEN, BLUE];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[5, 0, 2])
+            .unwrap();
+        let result = QuantizerWsmeans::default().quantize_input(&input, MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 2);
+        assert_eq!(result.color_to_count[&RED], 5);
+        assert_eq!(result.color_to_count[&BLUE], 2);
+    }
+
+    #[test]
+    fn near_max_weights_saturate() {
+        let pixels = [0xffff0000, 0xfffe0000];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[u32::MAX, 5])
+            .unwrap();
+        let result = QuantizerWsmeans::default().quantize_input(&input, 1);
+        assert_eq!(result.color_to_count.len(), 1);
+        assert_eq!(
+            result.color_to_count.values().copied().collect::<Vec<_>>(),
+            vec![u32::MAX]
+        );
+    }
 }
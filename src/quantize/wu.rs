@@ -1,17 +1,23 @@
 use indexmap::IndexMap;
 
-use crate::utils::color_utils;
+use crate::{error::ArgumentError, utils::color_utils};
 
-use super::{map::QuantizerMap, Quantizer, QuantizerResult};
+use super::{map::QuantizerMap, HistogramQuantizer, Quantizer, QuantizerInput, QuantizerResult};
 
+/// Wu's variance-minimizing quantizer. The histogram lives on the heap and is
+/// reused by later calls to [`Quantizer::quantize`].
 #[derive(Debug)]
 pub struct QuantizerWu {
-    weights: [u32; Self::TOTAL_SIZE],
-    moments_r: [u32; Self::TOTAL_SIZE],
-    moments_g: [u32; Self::TOTAL_SIZE],
-    moments_b: [u32; Self::TOTAL_SIZE],
-    moments: [f64; Self::TOTAL_SIZE],
+    index_bits: usize,
+    weights: Vec<i64>,
+    moments_r: Vec<i64>,
+    moments_g: Vec<i64>,
+    moments_b: Vec<i64>,
+    moments: Vec<f64>,
     cubes: Vec<Cube>,
+    /// Whether to map each distinct input color to the color of its cube in
+    /// [`QuantizerResult::input_pixel_to_cluster_pixel`].
+    pub return_input_pixel_to_cluster_pixel: bool,
 }
 
 impl Default for QuantizerWu {
@@ -21,15 +27,53 @@ impl Default for QuantizerWu {
 }
 
 impl Quantizer for QuantizerWu {
-    fn quantize(&mut self, pixels: &[u32], max_colors: u32) -> QuantizerResult {
-        let result = QuantizerMap.quantize(pixels, max_colors);
-        self.construct_histogram(result.color_to_count);
+    fn quantize_input(&mut self, input: &QuantizerInput, max_colors: u32) -> QuantizerResult {
+        let result = QuantizerMap.quantize_input(input, max_colors);
+        self.quantize_histogram(&result.color_to_count, max_colors)
+    }
+}
+
+impl HistogramQuantizer for QuantizerWu {
+    fn quantize_histogram(
+        &mut self,
+        count_by_color: &IndexMap<u32, u32>,
+        max_colors: u32,
+    ) -> QuantizerResult {
+        self.construct_histogram(count_by_color);
         self.compute_moments();
         let create_cubes_result = self.create_cubes(max_colors as usize);
         let results = self.create_result(create_cubes_result.result_count as usize);
+
+        let mut color_to_count = IndexMap::new();
+        for (_, color, population) in &results {
+            let count = color_to_count.entry(*color).or_insert(0u32);
+            *count = count.saturating_add(*population);
+        }
+
+        let mut input_pixel_to_cluster_pixel = IndexMap::new();
+        if self.return_input_pixel_to_cluster_pixel {
+            // Tag every histogram cell with the color of the cube containing it.
+            let mut tags = vec![0; self.total_size()];
+            for (cube_index, color, _) in &results {
+                let cube = &self.cubes[*cube_index];
+                for r in cube.r0 + 1..=cube.r1 {
+                    for g in cube.g0 + 1..=cube.g1 {
+                        for b in cube.b0 + 1..=cube.b1 {
+                            tags[self.get_index(r, g, b)] = *color;
+                        }
+                    }
+                }
+            }
+            for pixel in count_by_color.keys() {
+                let (i_r, i_g, i_b) = self.histogram_coordinates(*pixel);
+                input_pixel_to_cluster_pixel.insert(*pixel, tags[self.get_index(i_r, i_g, i_b)]);
+            }
+        }
+
         QuantizerResult {
-            color_to_count: results.iter().map(|e| (*e, 0)).collect(),
-            input_pixel_to_cluster_pixel: IndexMap::new(),
+            color_to_count,
+            input_pixel_to_cluster_pixel,
+            stats: None,
         }
     }
 }
@@ -38,46 +82,90 @@ impl QuantizerWu {
     // A histogram of all the input colors is constructed. It has the shape of a
     // cube. The cube would be too large if it contained all 16 million colors:
     // historical best practice is to use 5 bits  of the 8 in each channel,
-    // reducing the histogram to a volume of ~32,000.
-    pub const INDEX_BITS: usize = 5;
-    pub const MAX_INDEX: usize = 32;
-    pub const SIDE_LENGTH: usize = 33;
-    pub const TOTAL_SIZE: usize = 35937;
+    // reducing the histogram to a volume of ~32,000. Images with subtle
+    // gradients keep more of their colors with more bits.
+    pub const DEFAULT_INDEX_BITS: usize = 5;
+    pub const MIN_INDEX_BITS: usize = 4;
+    pub const MAX_INDEX_BITS: usize = 7;
 
     pub fn new() -> QuantizerWu {
         QuantizerWu {
-            weights: [0; Self::TOTAL_SIZE],
-            moments_r: [0; Self::TOTAL_SIZE],
-            moments_g: [0; Self::TOTAL_SIZE],
-            moments_b: [0; Self::TOTAL_SIZE],
-            moments: [0.0; Self::TOTAL_SIZE],
+            index_bits: Self::DEFAULT_INDEX_BITS,
+            weights: vec![],
+            moments_r: vec![],
+            moments_g: vec![],
+            moments_b: vec![],
+            moments: vec![],
             cubes: vec![],
+            return_input_pixel_to_cluster_pixel: false,
+        }
+    }
+
+    /// A quantizer keeping `index_bits` of the 8 bits in each channel, from
+    /// [`QuantizerWu::MIN_INDEX_BITS`] to [`QuantizerWu::MAX_INDEX_BITS`].
+    pub fn with_index_bits(index_bits: usize) -> Result<QuantizerWu, ArgumentError> {
+        if !(Self::MIN_INDEX_BITS..=Self::MAX_INDEX_BITS).contains(&index_bits) {
+            return Err(ArgumentError::new(format!(
+                "Invalid argument (index_bits: {}): must be between {} and {}",
+                index_bits,
+                Self::MIN_INDEX_BITS,
+                Self::MAX_INDEX_BITS
+            )));
         }
+        Ok(QuantizerWu {
+            index_bits,
+            ..QuantizerWu::new()
+        })
     }
 
+    pub fn index_bits(&self) -> usize {
+        self.index_bits
+    }
+
+    fn max_index(&self) -> usize {
+        1 << self.index_bits
+    }
+    fn side_length(&self) -> usize {
+        self.max_index() + 1
+    }
+    fn total_size(&self) -> usize {
+        self.side_length().pow(3)
+    }
     fn get_index(&self, r: usize, g: usize, b: usize) -> usize {
-        (r << (Self::INDEX_BITS * 2))
-            + (r << (Self::INDEX_BITS + 1))
-            + (g << Self::INDEX_BITS)
+        (r << (self.index_bits * 2))
+            + (r << (self.index_bits + 1))
+            + (g << self.index_bits)
             + r
             + g
             + b
     }
-    fn construct_histogram(&mut self, pixels: IndexMap<u32, u32>) {
-        self.weights = [0; Self::TOTAL_SIZE];
-        self.moments_r = [0; Self::TOTAL_SIZE];
-        self.moments_g = [0; Self::TOTAL_SIZE];
-        self.moments_b = [0; Self::TOTAL_SIZE];
-        self.moments = [0.0; Self::TOTAL_SIZE];
-        for (pixel, count) in pixels {
-            let red = color_utils::red_from_argb(pixel);
-            let green = color_utils::green_from_argb(pixel);
-            let blue = color_utils::blue_from_argb(pixel);
-            let bits_to_remove = 8 - Self::INDEX_BITS;
-            let i_r = (red >> bits_to_remove) + 1;
-            let i_g = (green >> bits_to_remove) + 1;
-            let i_b = (blue >> bits_to_remove) + 1;
-            let index = self.get_index(i_r as usize, i_g as usize, i_b as usize);
+    fn histogram_coordinates(&self, pixel: u32) -> (usize, usize, usize) {
+        let bits_to_remove = 8 - self.index_bits;
+        let i_r = (color_utils::red_from_argb(pixel) >> bits_to_remove) + 1;
+        let i_g = (color_utils::green_from_argb(pixel) >> bits_to_remove) + 1;
+        let i_b = (color_utils::blue_from_argb(pixel) >> bits_to_remove) + 1;
+        (i_r as usize, i_g as usize, i_b as usize)
+    }
+    fn construct_histogram(&mut self, pixels: &IndexMap<u32, u32>) {
+        let total_size = self.total_size();
+        for moment in [
+            &mut self.weights,
+            &mut self.moments_r,
+            &mut self.moments_g,
+            &mut self.moments_b,
+        ] {
+            moment.clear();
+            moment.resize(total_size, 0);
+        }
+        self.moments.clear();
+        self.moments.resize(total_size, 0.0);
+        for (&pixel, &count) in pixels {
+            let red = color_utils::red_from_argb(pixel) as i64;
+            let green = color_utils::green_from_argb(pixel) as i64;
+            let blue = color_utils::blue_from_argb(pixel) as i64;
+            let count = count as i64;
+            let (i_r, i_g, i_b) = self.histogram_coordinates(pixel);
+            let index = self.get_index(i_r, i_g, i_b);
             self.weights[index] += count;
             self.moments_r[index] += red * count;
             self.moments_g[index] += green * count;
@@ -86,19 +174,20 @@ impl QuantizerWu {
         }
     }
     fn compute_moments(&mut self) {
-        for r in 1..Self::SIDE_LENGTH {
-            let mut area = vec![0; Self::SIDE_LENGTH];
-            let mut area_r = vec![0; Self::SIDE_LENGTH];
-            let mut area_g = vec![0; Self::SIDE_LENGTH];
-            let mut area_b = vec![0; Self::SIDE_LENGTH];
-            let mut area2 = vec![0.0; Self::SIDE_LENGTH];
-            for g in 1..Self::SIDE_LENGTH {
+        let side_length = self.side_length();
+        for r in 1..side_length {
+            let mut area = vec![0; side_length];
+            let mut area_r = vec![0; side_length];
+            let mut area_g = vec![0; side_length];
+            let mut area_b = vec![0; side_length];
+            let mut area2 = vec![0.0; side_length];
+            for g in 1..side_length {
                 let mut line = 0;
                 let mut line_r = 0;
                 let mut line_g = 0;
                 let mut line_b = 0;
                 let mut line2 = 0.0;
-                for b in 1..Self::SIDE_LENGTH {
+                for b in 1..side_length {
                     let index = self.get_index(r, g, b);
                     line += self.weights[index];
                     line_r += self.moments_r[index];
@@ -126,10 +215,11 @@ impl QuantizerWu {
         self.cubes = vec![Cube::default(); max_color_count];
         let mut generated_color_count = max_color_count;
         {
+            let max_index = self.max_index();
             let cube_ref = &mut self.cubes[0];
-            cube_ref.set_r1(Self::MAX_INDEX);
-            cube_ref.set_g1(Self::MAX_INDEX);
-            cube_ref.set_b1(Self::MAX_INDEX);
+            cube_ref.set_r1(max_index);
+            cube_ref.set_g1(max_index);
+            cube_ref.set_b1(max_index);
         }
 
         let mut volume_variance = vec![0.0; max_color_count];
@@ -161,9 +251,9 @@ impl QuantizerWu {
 
             next = 0;
             let mut temp = volume_variance[0];
-            for j in 1..=i {
-                if volume_variance[j] > temp {
-                    temp = volume_variance[j];
+            for (j, variance) in volume_variance.iter().enumerate().take(i + 1).skip(1) {
+                if *variance > temp {
+                    temp = *variance;
                     next = j;
                 }
             }
@@ -178,17 +268,19 @@ impl QuantizerWu {
             result_count: generated_color_count as u32,
         }
     }
-    fn create_result(&self, color_count: usize) -> Vec<u32> {
+    /// The index, color and population of each non-empty cube.
+    fn create_result(&self, color_count: usize) -> Vec<(usize, u32, u32)> {
         let mut colors = vec![];
         for i in 0..color_count {
             let cube = &self.cubes[i];
             let weight = self.volume(cube, &self.weights);
-            if weight > 0 {
-                let r = self.volume(cube, &self.moments_r) / weight;
-                let g = self.volume(cube, &self.moments_g) / weight;
-                let b = self.volume(cube, &self.moments_b) / weight;
-                let color = color_utils::argb_from_rgb(r, g, b);
-                colors.push(color);
+            if let (Some(r), Some(g), Some(b)) = (
+                self.volume(cube, &self.moments_r).checked_div(weight),
+                self.volume(cube, &self.moments_g).checked_div(weight),
+                self.volume(cube, &self.moments_b).checked_div(weight),
+            ) {
+                let color = color_utils::argb_from_rgb(r as u32, g as u32, b as u32);
+                colors.push((i, color, u32::try_from(weight).unwrap_or(u32::MAX)));
             }
         }
         colors
@@ -273,16 +365,17 @@ impl QuantizerWu {
         two.vol = (two.r1 - two.r0) * (two.g1 - two.g0) * (two.b1 - two.b0);
         true
     }
+    #[allow(clippy::too_many_arguments)]
     fn maximize(
         &self,
         cube: &Cube,
         direction: Direction,
         first: usize,
         last: usize,
-        whole_r: u32,
-        whole_g: u32,
-        whole_b: u32,
-        whole_w: u32,
+        whole_r: i64,
+        whole_g: i64,
+        whole_b: i64,
+        whole_w: i64,
     ) -> MaximizeResult {
         let bottom_r = self.bottom(cube, direction.clone(), &self.moments_r);
         let bottom_g = self.bottom(cube, direction.clone(), &self.moments_g);
@@ -302,9 +395,7 @@ impl QuantizerWu {
                 continue;
             }
 
-            let mut temp_numerator = (half_r * half_r) + (half_g * half_g) + (half_b * half_b);
-            let mut temp_denominator = half_w;
-            let mut temp = temp_numerator / temp_denominator;
+            let mut temp = Self::squared_sum(half_r, half_g, half_b) / half_w as f64;
 
             half_r = whole_r - half_r;
             half_g = whole_g - half_g;
@@ -313,12 +404,10 @@ impl QuantizerWu {
             if half_w == 0 {
                 continue;
             }
-            temp_numerator = (half_r * half_r) + (half_g * half_g) + (half_b * half_b);
-            temp_denominator = half_w;
-            temp += temp_numerator / temp_denominator;
+            temp += Self::squared_sum(half_r, half_g, half_b) / half_w as f64;
 
-            if temp as f64 > max {
-                max = temp as f64;
+            if temp > max {
+                max = temp;
                 cut = i as isize;
             }
         }
@@ -328,7 +417,7 @@ impl QuantizerWu {
         }
     }
 
-    fn volume(&self, cube: &Cube, moment: &[u32]) -> u32 {
+    fn volume(&self, cube: &Cube, moment: &[i64]) -> i64 {
         moment[self.get_index(cube.r1, cube.g1, cube.b1)]
             - moment[self.get_index(cube.r1, cube.g1, cube.b0)]
             - moment[self.get_index(cube.r1, cube.g0, cube.b1)]
@@ -338,58 +427,30 @@ impl QuantizerWu {
             + moment[self.get_index(cube.r0, cube.g0, cube.b1)]
             - moment[self.get_index(cube.r0, cube.g0, cube.b0)]
     }
-    fn bottom(&self, cube: &Cube, direction: Direction, moment: &[u32]) -> u32 {
-        let res: i32 = match direction {
+    fn bottom(&self, cube: &Cube, direction: Direction, moment: &[i64]) -> i64 {
+        match direction {
             Direction::Red => {
-                let temp0: i32 = moment[self.get_index(cube.r0, cube.g1, cube.b1)]
-                    .try_into()
-                    .unwrap();
-                let temp1: i32 = moment[self.get_index(cube.r0, cube.g1, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                let temp2: i32 = moment[self.get_index(cube.r0, cube.g0, cube.b1)]
-                    .try_into()
-                    .unwrap();
-                let temp3: i32 = moment[self.get_index(cube.r0, cube.g0, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                -temp0 + temp1 + temp2 - temp3
+                -moment[self.get_index(cube.r0, cube.g1, cube.b1)]
+                    + moment[self.get_index(cube.r0, cube.g1, cube.b0)]
+                    + moment[self.get_index(cube.r0, cube.g0, cube.b1)]
+                    - moment[self.get_index(cube.r0, cube.g0, cube.b0)]
             }
             Direction::Green => {
-                let temp0: i32 = moment[self.get_index(cube.r1, cube.g0, cube.b1)]
-                    .try_into()
-                    .unwrap();
-                let temp1: i32 = moment[self.get_index(cube.r1, cube.g0, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                let temp2: i32 = moment[self.get_index(cube.r0, cube.g0, cube.b1)]
-                    .try_into()
-                    .unwrap();
-                let temp3: i32 = moment[self.get_index(cube.r0, cube.g0, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                -temp0 + temp1 + temp2 - temp3
+                -moment[self.get_index(cube.r1, cube.g0, cube.b1)]
+                    + moment[self.get_index(cube.r1, cube.g0, cube.b0)]
+                    + moment[self.get_index(cube.r0, cube.g0, cube.b1)]
+                    - moment[self.get_index(cube.r0, cube.g0, cube.b0)]
             }
             Direction::Blue => {
-                let temp0: i32 = moment[self.get_index(cube.r1, cube.g1, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                let temp1: i32 = moment[self.get_index(cube.r1, cube.g0, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                let temp2: i32 = moment[self.get_index(cube.r0, cube.g1, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                let temp3: i32 = moment[self.get_index(cube.r0, cube.g0, cube.b0)]
-                    .try_into()
-                    .unwrap();
-                -temp0 + temp1 + temp2 - temp3
+                -moment[self.get_index(cube.r1, cube.g1, cube.b0)]
+                    + moment[self.get_index(cube.r1, cube.g0, cube.b0)]
+                    + moment[self.get_index(cube.r0, cube.g1, cube.b0)]
+                    - moment[self.get_index(cube.r0, cube.g0, cube.b0)]
             }
-        };
-        res.try_into().unwrap()
+        }
     }
 
-    fn top(&self, cube: &Cube, direction: Direction, position: usize, moment: &[u32]) -> u32 {
+    fn top(&self, cube: &Cube, direction: Direction, position: usize, moment: &[i64]) -> i64 {
         match direction {
             Direction::Red => {
                 moment[self.get_index(position, cube.g1, cube.b1)]
@@ -411,6 +472,12 @@ impl QuantizerWu {
             }
         }
     }
+    /// `r² + g² + b²`, in floating point since the squares of large moments
+    /// overflow integers.
+    fn squared_sum(r: i64, g: i64, b: i64) -> f64 {
+        let (r, g, b) = (r as f64, g as f64, b as f64);
+        r * r + g * g + b * b
+    }
     fn variance(&self, cube: &Cube) -> f64 {
         let dr = self.volume(cube, &self.moments_r);
         let dg = self.volume(cube, &self.moments_g);
@@ -424,7 +491,7 @@ impl QuantizerWu {
             + self.moments[self.get_index(cube.r0, cube.g0, cube.b1)]
             - self.moments[self.get_index(cube.r0, cube.g0, cube.b0)];
 
-        let hypotenuse = (dr * dr + dg * dg + db * db) as f64;
+        let hypotenuse = Self::squared_sum(dr, dg, db);
         let volume_ = self.volume(cube, &self.weights) as f64;
         xx - hypotenuse / volume_
     }
@@ -481,9 +548,9 @@ impl Cube {
 
 #[cfg(test)]
 mod test {
-    use indexmap::IndexSet;
+    use indexmap::{IndexMap, IndexSet};
 
-    use crate::quantize::Quantizer;
+    use crate::quantize::{Quantizer, QuantizerInput};
 
     use super::QuantizerWu;
 
@@ -548,7 +615,7 @@ mod test {
         let pixels = vec![RED, RED, GREEN, GREEN, GREEN];
         let result = wu.quantize(&pixels, MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
-        let color_set: IndexSet<u32> = colors.iter().map(|color| *color).collect();
+        let color_set: IndexSet<u32> = colors.iter().copied().collect();
         assert_eq!(color_set.len(), 2);
         assert_eq!(colors[0], GREEN);
         assert_eq!(colors[1], RED);
@@ -560,10 +627,107 @@ mod test {
         let pixels = vec![RED, GREEN, BLUE];
         let result = wu.quantize(&pixels, MAX_COLORS);
         let colors: Vec<u32> = result.color_to_count.keys().copied().collect();
-        let color_set: IndexSet<u32> = colors.iter().map(|color| *color).collect();
+        let color_set: IndexSet<u32> = colors.iter().copied().collect();
         assert_eq!(color_set.len(), 3);
         assert_eq!(colors[0], BLUE);
         assert_eq!(colors[1], RED);
         assert_eq!(colors[2], GREEN);
     }
+
+    #[test]
+    fn reports_populations() {
+        let mut wu = QuantizerWu::new();
+        let pixels = vec![RED, RED, GREEN, GREEN, GREEN];
+        let result = wu.quantize(&pixels, MAX_COLORS);
+        assert_eq!(result.color_to_count[&RED], 2);
+        assert_eq!(result.color_to_count[&GREEN], 3);
+        assert!(result.input_pixel_to_cluster_pixel.is_empty());
+    }
+
+    #[test]
+    fn maps_input_pixels_to_cubes() {
+        let mut wu = QuantizerWu::new();
+        wu.return_input_pixel_to_cluster_pixel = true;
+        let pixels = vec![RED, 0xfffe0101, GREEN, BLUE];
+        let result = wu.quantize(&pixels, 3);
+        assert_eq!(result.color_to_count.values().sum::<u32>(), 4);
+        assert_eq!(result.input_pixel_to_cluster_pixel.len(), 4);
+        let red_cluster = result.input_pixel_to_cluster_pixel[&RED];
+        assert_eq!(
+            result.input_pixel_to_cluster_pixel[&0xfffe0101],
+            red_cluster
+        );
+        assert_eq!(result.color_to_count[&red_cluster], 2);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&GREEN], GREEN);
+        assert_eq!(result.input_pixel_to_cluster_pixel[&BLUE], BLUE);
+    }
+
+    #[test]
+    fn index_bits_are_validated() {
+        assert!(QuantizerWu::with_index_bits(3).is_err());
+        assert!(QuantizerWu::with_index_bits(8).is_err());
+        assert_eq!(QuantizerWu::with_index_bits(7).unwrap().index_bits(), 7);
+    }
+
+    #[test]
+    fn more_index_bits_keep_close_colors_apart() {
+        let pixels = vec![0xff101010, 0xff121212];
+        let result = QuantizerWu::new().quantize(&pixels, MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 1);
+        let result = QuantizerWu::with_index_bits(7)
+            .unwrap()
+            .quantize(&pixels, MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 2);
+    }
+
+    #[test]
+    fn reuses_storage_across_calls() {
+        let mut wu = QuantizerWu::new();
+        wu.quantize(&[RED, GREEN, BLUE], MAX_COLORS);
+        let result = wu.quantize(&[RED, RED], MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 1);
+        assert_eq!(result.color_to_count[&RED], 2);
+    }
+
+    #[test]
+    fn large_populations_do_not_overflow() {
+        let mut wu = QuantizerWu::new();
+        let histogram = IndexMap::from([(0xffffffff, 40_000_000), (0xff808080, 40_000_000)]);
+        wu.construct_histogram(&histogram);
+        wu.compute_moments();
+        let create_cubes_result = wu.create_cubes(MAX_COLORS as usize);
+        let results = wu.create_result(create_cubes_result.result_count as usize);
+        let colors: Vec<(u32, u32)> = results.iter().map(|(_, c, w)| (*c, *w)).collect();
+        assert_eq!(colors.len(), 2);
+        assert!(colors.contains(&(0xffffffff, 40_000_000)));
+        assert!(colors.contains(&(0xff808080, 40_000_000)));
+    }
+
+    #[test]
+    fn weights_become_populations() {
+        let pixels = [RED, GREEN, BLUE];
+        let input = QuantizerInput::new(&pixels)
+            .with_mask(&[true, false, true])
+            .unwrap()
+            .with_weights(&[4, 1, 6])
+            .unwrap();
+        let result = QuantizerWu::new().quantize_input(&input, MAX_COLORS);
+        assert_eq!(result.color_to_count.len(), 2);
+        assert_eq!(result.color_to_count[&RED], 4);
+        assert_eq!(result.color_to_count[&BLUE], 6);
+    }
+
+    #[test]
+    fn near_max_weights_saturate() {
+        let pixels = [0xffff0000, 0xfffe0000];
+        let input = QuantizerInput::new(&pixels)
+            .with_weights(&[u32::MAX, 5])
+            .unwrap();
+        let result = QuantizerWu::default().quantize_input(&input, 1);
+        assert_eq!(result.color_to_count.len(), 1);
+        assert_eq!(
+            result.color_to_count.values().copied().collect::<Vec<_>>(),
+            vec![u32::MAX]
+        );
+    }
 }
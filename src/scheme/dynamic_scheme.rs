@@ -0,0 +1,721 @@
+use crate::{
+    dislike::DislikeAnalyzer,
+    dynamiccolor::{DynamicColor, MaterialDynamicColors, Variant},
+    hct::Hct,
+    palette::TonalPalette,
+    temperature::TemperatureCache,
+    utils::math_utils::sanitize_degrees_double,
+};
+
+// Palettes are measured in the same viewing conditions as the source color.
+fn palette(source_color_hct: &Hct, hue: f64, chroma: f64) -> TonalPalette {
+    TonalPalette::of_in_viewing_conditions(hue, chroma, source_color_hct.viewing_conditions())
+}
+
+/// Constructed by a set of values representing the current UI state (such as
+/// whether or not its dark theme, what the theme style is, etc.), and
+/// provides a set of [`TonalPalette`]s that can create colors that fit in
+/// with the theme style. Used by [`DynamicColor`] to resolve into a color.
+#[derive(Debug)]
+pub struct DynamicScheme {
+    /// The source color of the theme as an ARGB integer.
+    pub source_color_argb: u32,
+    /// The source color of the theme in HCT.
+    pub source_color_hct: Hct,
+    /// The variant, or style, of the theme.
+    pub variant: Variant,
+    /// Whether or not the scheme is in 'dark mode' or 'light mode'.
+    pub is_dark: bool,
+    /// Value from -1 to 1. -1 represents minimum contrast, 0 represents
+    /// standard (i.e. the design as spec'd), and 1 represents maximum contrast.
+    pub contrast_level: f64,
+    /// Given a tone, produces a color. Hue and chroma of the color are
+    /// specified in the design specification of the variant. Usually colorful.
+    pub primary_palette: TonalPalette,
+    /// Given a tone, produces a color. Hue and chroma of the color are
+    /// specified in the design specification of the variant. Usually less
+    /// colorful.
+    pub secondary_palette: TonalPalette,
+    /// Given a tone, produces a color. Hue and chroma of the color are
+    /// specified in the design specification of the variant. Usually a
+    /// different hue from primary and colorful.
+    pub tertiary_palette: TonalPalette,
+    /// Given a tone, produces a color. Hue and chroma of the color are
+    /// specified in the design specification of the variant. Usually not
+    /// colorful at all, intended for background & surface colors.
+    pub neutral_palette: TonalPalette,
+    /// Given a tone, produces a color. Hue and chroma of the color are
+    /// specified in the design specification of the variant. Usually not
+    /// colorful, but slightly more colorful than Neutral. Intended for
+    /// backgrounds & surfaces.
+    pub neutral_variant_palette: TonalPalette,
+    /// Given a tone, produces a reddish, colorful, color.
+    pub error_palette: TonalPalette,
+}
+
+impl DynamicScheme {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        source_color_hct: Hct,
+        variant: Variant,
+        is_dark: bool,
+        contrast_level: f64,
+        primary_palette: TonalPalette,
+        secondary_palette: TonalPalette,
+        tertiary_palette: TonalPalette,
+        neutral_palette: TonalPalette,
+        neutral_variant_palette: TonalPalette,
+    ) -> DynamicScheme {
+        DynamicScheme {
+            source_color_argb: source_color_hct.to_int(),
+            source_color_hct,
+            variant,
+            is_dark,
+            contrast_level,
+            primary_palette,
+            secondary_palette,
+            tertiary_palette,
+            neutral_palette,
+            neutral_variant_palette,
+            error_palette: palette(&source_color_hct, 25.0, 84.0),
+        }
+    }
+
+    /// Creates a scheme of the given `variant` from `source_color_hct`.
+    pub fn from_variant(
+        source_color_hct: Hct,
+        variant: Variant,
+        is_dark: bool,
+        contrast_level: f64,
+    ) -> DynamicScheme {
+        match variant {
+            Variant::Monochrome => {
+                DynamicScheme::monochrome(source_color_hct, is_dark, contrast_level)
+            }
+            Variant::Neutral => DynamicScheme::neutral(source_color_hct, is_dark, contrast_level),
+            Variant::TonalSpot => {
+                DynamicScheme::tonal_spot(source_color_hct, is_dark, contrast_level)
+            }
+            Variant::Vibrant => DynamicScheme::vibrant(source_color_hct, is_dark, contrast_level),
+            Variant::Expressive => {
+                DynamicScheme::expressive(source_color_hct, is_dark, contrast_level)
+            }
+            Variant::Content => DynamicScheme::content(source_color_hct, is_dark, contrast_level),
+            Variant::Fidelity => DynamicScheme::fidelity(source_color_hct, is_dark, contrast_level),
+            Variant::Rainbow => DynamicScheme::rainbow(source_color_hct, is_dark, contrast_level),
+            Variant::FruitSalad => {
+                DynamicScheme::fruit_salad(source_color_hct, is_dark, contrast_level)
+            }
+        }
+    }
+
+    /// A monochrome theme, colors are purely black / white / gray.
+    pub fn monochrome(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::Monochrome,
+            is_dark,
+            contrast_level,
+            palette(&source_color_hct, source_color_hct.hue(), 0.0),
+            palette(&source_color_hct, source_color_hct.hue(), 0.0),
+            palette(&source_color_hct, source_color_hct.hue(), 0.0),
+            palette(&source_color_hct, source_color_hct.hue(), 0.0),
+            palette(&source_color_hct, source_color_hct.hue(), 0.0),
+        )
+    }
+
+    /// A theme that's slightly more chromatic than monochrome, which is purely
+    /// black / white / gray.
+    pub fn neutral(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::Neutral,
+            is_dark,
+            contrast_level,
+            palette(&source_color_hct, source_color_hct.hue(), 12.0),
+            palette(&source_color_hct, source_color_hct.hue(), 8.0),
+            palette(&source_color_hct, source_color_hct.hue(), 16.0),
+            palette(&source_color_hct, source_color_hct.hue(), 2.0),
+            palette(&source_color_hct, source_color_hct.hue(), 2.0),
+        )
+    }
+
+    /// A calm theme, sedated colors that aren't particularly chromatic.
+    pub fn tonal_spot(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::TonalSpot,
+            is_dark,
+            contrast_level,
+            palette(&source_color_hct, source_color_hct.hue(), 36.0),
+            palette(&source_color_hct, source_color_hct.hue(), 16.0),
+            palette(
+                &source_color_hct,
+                sanitize_degrees_double(source_color_hct.hue() + 60.0),
+                24.0,
+            ),
+            palette(&source_color_hct, source_color_hct.hue(), 6.0),
+            palette(&source_color_hct, source_color_hct.hue(), 8.0),
+        )
+    }
+
+    /// A loud theme, colorfulness is maximum for Primary palette, increased for
+    /// others.
+    pub fn vibrant(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        const HUES: [f64; 9] = [0.0, 41.0, 61.0, 101.0, 131.0, 181.0, 251.0, 301.0, 360.0];
+        const SECONDARY_ROTATIONS: [f64; 9] =
+            [18.0, 15.0, 10.0, 12.0, 15.0, 18.0, 15.0, 12.0, 12.0];
+        const TERTIARY_ROTATIONS: [f64; 9] = [35.0, 30.0, 20.0, 25.0, 30.0, 35.0, 30.0, 25.0, 25.0];
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::Vibrant,
+            is_dark,
+            contrast_level,
+            palette(&source_color_hct, source_color_hct.hue(), 200.0),
+            palette(
+                &source_color_hct,
+                DynamicScheme::get_rotated_hue(source_color_hct, &HUES, &SECONDARY_ROTATIONS),
+                24.0,
+            ),
+            palette(
+                &source_color_hct,
+                DynamicScheme::get_rotated_hue(source_color_hct, &HUES, &TERTIARY_ROTATIONS),
+                32.0,
+            ),
+            palette(&source_color_hct, source_color_hct.hue(), 10.0),
+            palette(&source_color_hct, source_color_hct.hue(), 12.0),
+        )
+    }
+
+    /// A playful theme - the source color's hue does not appear in the theme.
+    pub fn expressive(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        const HUES: [f64; 9] = [0.0, 21.0, 51.0, 121.0, 151.0, 191.0, 271.0, 321.0, 360.0];
+        const SECONDARY_ROTATIONS: [f64; 9] =
+            [45.0, 95.0, 45.0, 20.0, 45.0, 90.0, 45.0, 45.0, 45.0];
+        const TERTIARY_ROTATIONS: [f64; 9] =
+            [120.0, 120.0, 20.0, 45.0, 20.0, 15.0, 20.0, 120.0, 120.0];
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::Expressive,
+            is_dark,
+            contrast_level,
+            palette(
+                &source_color_hct,
+                sanitize_degrees_double(source_color_hct.hue() + 240.0),
+                40.0,
+            ),
+            palette(
+                &source_color_hct,
+                DynamicScheme::get_rotated_hue(source_color_hct, &HUES, &SECONDARY_ROTATIONS),
+                24.0,
+            ),
+            palette(
+                &source_color_hct,
+                DynamicScheme::get_rotated_hue(source_color_hct, &HUES, &TERTIARY_ROTATIONS),
+                32.0,
+            ),
+            palette(
+                &source_color_hct,
+                sanitize_degrees_double(source_color_hct.hue() + 15.0),
+                8.0,
+            ),
+            palette(
+                &source_color_hct,
+                sanitize_degrees_double(source_color_hct.hue() + 15.0),
+                12.0,
+            ),
+        )
+    }
+
+    /// A scheme that places the source color in Scheme.primaryContainer.
+    ///
+    /// Primary Container is the source color, adjusted for color relativity.
+    /// It maintains constant appearance in light mode and dark mode.
+    /// This adds ~5 tone in light mode, and subtracts ~5 tone in dark mode.
+    ///
+    /// Tertiary Container is the last of the colors from
+    /// [`TemperatureCache::analogous`] with a count of 3 on a color wheel of 6
+    /// divisions, adjusted by [`DislikeAnalyzer::fix_if_disliked`].
+    pub fn content(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::Content,
+            is_dark,
+            contrast_level,
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                source_color_hct.chroma(),
+            ),
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                (source_color_hct.chroma() - 32.0).max(source_color_hct.chroma() * 0.5),
+            ),
+            TonalPalette::from_hct(DislikeAnalyzer::fix_if_disliked(
+                *TemperatureCache::new(source_color_hct)
+                    .analogous(3, 6)
+                    .last()
+                    .unwrap(),
+            )),
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                source_color_hct.chroma() / 8.0,
+            ),
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                source_color_hct.chroma() / 8.0 + 4.0,
+            ),
+        )
+    }
+
+    /// A scheme that places the source color in Scheme.primaryContainer.
+    ///
+    /// Primary Container is the source color, adjusted for color relativity.
+    /// It maintains constant appearance in light mode and dark mode.
+    /// This adds ~5 tone in light mode, and subtracts ~5 tone in dark mode.
+    ///
+    /// Tertiary Container is the complement of the source color.
+    pub fn fidelity(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::Fidelity,
+            is_dark,
+            contrast_level,
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                source_color_hct.chroma(),
+            ),
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                (source_color_hct.chroma() - 32.0).max(source_color_hct.chroma() * 0.5),
+            ),
+            TonalPalette::from_hct(DislikeAnalyzer::fix_if_disliked(
+                TemperatureCache::new(source_color_hct).complement(),
+            )),
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                source_color_hct.chroma() / 8.0,
+            ),
+            palette(
+                &source_color_hct,
+                source_color_hct.hue(),
+                source_color_hct.chroma() / 8.0 + 4.0,
+            ),
+        )
+    }
+
+    /// A playful theme - the source color's hue does not appear in the theme.
+    pub fn rainbow(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::Rainbow,
+            is_dark,
+            contrast_level,
+            palette(&source_color_hct, source_color_hct.hue(), 48.0),
+            palette(&source_color_hct, source_color_hct.hue(), 16.0),
+            palette(
+                &source_color_hct,
+                sanitize_degrees_double(source_color_hct.hue() + 60.0),
+                24.0,
+            ),
+            palette(&source_color_hct, source_color_hct.hue(), 0.0),
+            palette(&source_color_hct, source_color_hct.hue(), 0.0),
+        )
+    }
+
+    /// A playful theme - the source color's hue does not appear in the theme.
+    pub fn fruit_salad(source_color_hct: Hct, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        DynamicScheme::new(
+            source_color_hct,
+            Variant::FruitSalad,
+            is_dark,
+            contrast_level,
+            palette(
+                &source_color_hct,
+                sanitize_degrees_double(source_color_hct.hue() - 50.0),
+                48.0,
+            ),
+            palette(
+                &source_color_hct,
+                sanitize_degrees_double(source_color_hct.hue() - 50.0),
+                36.0,
+            ),
+            palette(&source_color_hct, source_color_hct.hue(), 36.0),
+            palette(&source_color_hct, source_color_hct.hue(), 10.0),
+            palette(&source_color_hct, source_color_hct.hue(), 16.0),
+        )
+    }
+
+    /// Given a set of hues and set of hue rotations, locate which hues the
+    /// source color's hue is between, apply the rotation at the same index as
+    /// the first hue in the range, and return the rotated hue.
+    ///
+    /// `source_color` the color whose hue should be rotated.
+    /// `hues` A set of hues.
+    /// `rotations` A set of hue rotations.
+    ///
+    /// Returns the source color's hue, rotated by the rotation at the same
+    /// index as the first hue in the range the source color's hue falls in.
+    pub fn get_rotated_hue(source_color: Hct, hues: &[f64], rotations: &[f64]) -> f64 {
+        let source_hue = source_color.hue();
+        assert_eq!(hues.len(), rotations.len());
+        if rotations.len() == 1 {
+            return sanitize_degrees_double(source_color.hue() + rotations[0]);
+        }
+        let size = hues.len();
+        for i in 0..=(size - 2) {
+            let this_hue = hues[i];
+            let next_hue = hues[i + 1];
+            if this_hue < source_hue && source_hue < next_hue {
+                return sanitize_degrees_double(source_hue + rotations[i]);
+            }
+        }
+        // If this statement executes, something is wrong, there should have been
+        // a rotation found using the arrays.
+        source_hue
+    }
+
+    /// Returns the HCT representation of `dynamic_color` in this scheme.
+    pub fn get_hct(&self, dynamic_color: &DynamicColor) -> Hct {
+        dynamic_color.get_hct(self)
+    }
+
+    /// Returns the ARGB representation of `dynamic_color` in this scheme.
+    pub fn get_argb(&self, dynamic_color: &DynamicColor) -> u32 {
+        dynamic_color.get_argb(self)
+    }
+
+    pub fn primary_palette_key_color(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::primary_palette_key_color())
+    }
+
+    pub fn secondary_palette_key_color(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::secondary_palette_key_color())
+    }
+
+    pub fn tertiary_palette_key_color(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::tertiary_palette_key_color())
+    }
+
+    pub fn neutral_palette_key_color(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::neutral_palette_key_color())
+    }
+
+    pub fn neutral_variant_palette_key_color(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::neutral_variant_palette_key_color())
+    }
+
+    pub fn background(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::background())
+    }
+
+    pub fn on_background(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_background())
+    }
+
+    pub fn surface(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface())
+    }
+
+    pub fn surface_dim(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_dim())
+    }
+
+    pub fn surface_bright(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_bright())
+    }
+
+    pub fn surface_container_lowest(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_container_lowest())
+    }
+
+    pub fn surface_container_low(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_container_low())
+    }
+
+    pub fn surface_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_container())
+    }
+
+    pub fn surface_container_high(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_container_high())
+    }
+
+    pub fn surface_container_highest(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_container_highest())
+    }
+
+    pub fn on_surface(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_surface())
+    }
+
+    pub fn surface_variant(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_variant())
+    }
+
+    pub fn on_surface_variant(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_surface_variant())
+    }
+
+    pub fn inverse_surface(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::inverse_surface())
+    }
+
+    pub fn inverse_on_surface(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::inverse_on_surface())
+    }
+
+    pub fn outline(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::outline())
+    }
+
+    pub fn outline_variant(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::outline_variant())
+    }
+
+    pub fn shadow(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::shadow())
+    }
+
+    pub fn scrim(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::scrim())
+    }
+
+    pub fn surface_tint(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::surface_tint())
+    }
+
+    pub fn primary(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::primary())
+    }
+
+    pub fn on_primary(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_primary())
+    }
+
+    pub fn primary_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::primary_container())
+    }
+
+    pub fn on_primary_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_primary_container())
+    }
+
+    pub fn inverse_primary(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::inverse_primary())
+    }
+
+    pub fn secondary(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::secondary())
+    }
+
+    pub fn on_secondary(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_secondary())
+    }
+
+    pub fn secondary_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::secondary_container())
+    }
+
+    pub fn on_secondary_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_secondary_container())
+    }
+
+    pub fn tertiary(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::tertiary())
+    }
+
+    pub fn on_tertiary(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_tertiary())
+    }
+
+    pub fn tertiary_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::tertiary_container())
+    }
+
+    pub fn on_tertiary_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_tertiary_container())
+    }
+
+    pub fn error(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::error())
+    }
+
+    pub fn on_error(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_error())
+    }
+
+    pub fn error_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::error_container())
+    }
+
+    pub fn on_error_container(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_error_container())
+    }
+
+    pub fn primary_fixed(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::primary_fixed())
+    }
+
+    pub fn primary_fixed_dim(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::primary_fixed_dim())
+    }
+
+    pub fn on_primary_fixed(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_primary_fixed())
+    }
+
+    pub fn on_primary_fixed_variant(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_primary_fixed_variant())
+    }
+
+    pub fn secondary_fixed(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::secondary_fixed())
+    }
+
+    pub fn secondary_fixed_dim(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::secondary_fixed_dim())
+    }
+
+    pub fn on_secondary_fixed(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_secondary_fixed())
+    }
+
+    pub fn on_secondary_fixed_variant(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_secondary_fixed_variant())
+    }
+
+    pub fn tertiary_fixed(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::tertiary_fixed())
+    }
+
+    pub fn tertiary_fixed_dim(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::tertiary_fixed_dim())
+    }
+
+    pub fn on_tertiary_fixed(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_tertiary_fixed())
+    }
+
+    pub fn on_tertiary_fixed_variant(&self) -> u32 {
+        self.get_argb(&MaterialDynamicColors::on_tertiary_fixed_variant())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dynamiccolor::Variant,
+        hct::Hct,
+        scheme::DynamicScheme,
+        temperature::TemperatureCache,
+        utils::{
+            color_utils::{blue_from_argb, green_from_argb, red_from_argb},
+            contrast::ratio_of_argb,
+        },
+    };
+
+    const VARIANTS: [Variant; 9] = [
+        Variant::Monochrome,
+        Variant::Neutral,
+        Variant::TonalSpot,
+        Variant::Vibrant,
+        Variant::Expressive,
+        Variant::Content,
+        Variant::Fidelity,
+        Variant::Rainbow,
+        Variant::FruitSalad,
+    ];
+
+    #[test]
+    fn get_rotated_hue_picks_range() {
+        let hues = [0.0, 41.0, 61.0, 101.0, 131.0, 181.0, 251.0, 301.0, 360.0];
+        let rotations = [18.0, 15.0, 10.0, 12.0, 15.0, 18.0, 15.0, 12.0, 12.0];
+        let hct = Hct::new(50.0, 40.0, 50.0);
+        let rotated = DynamicScheme::get_rotated_hue(hct, &hues, &rotations);
+        assert!((rotated - (hct.hue() + 15.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tonal_spot_standard_contrast() {
+        let light = DynamicScheme::tonal_spot(Hct::from_int(0xff0000ff), false, 0.0);
+        assert_eq!(light.primary(), 0xff555992);
+        assert_eq!(light.primary_container(), 0xffe0e0ff);
+        assert_eq!(light.on_primary_container(), 0xff11144b);
+        assert_eq!(light.surface(), 0xfffbf8ff);
+        assert_eq!(light.on_surface(), 0xff1b1b21);
+
+        let dark = DynamicScheme::tonal_spot(Hct::from_int(0xff0000ff), true, 0.0);
+        assert_eq!(dark.primary(), 0xffbec2ff);
+        assert_eq!(dark.primary_container(), 0xff3e4278);
+        assert_eq!(dark.on_primary_container(), 0xffe0e0ff);
+        assert_eq!(dark.surface(), 0xff131318);
+        assert_eq!(dark.on_surface(), 0xffe4e1e9);
+    }
+
+    #[test]
+    fn from_variant_matches_constructor() {
+        let source = Hct::from_int(0xff4285f4);
+        for variant in VARIANTS {
+            let scheme = DynamicScheme::from_variant(source, variant, false, 0.0);
+            assert_eq!(scheme.variant, variant);
+            assert_eq!(scheme.source_color_argb, 0xff4285f4);
+        }
+        let vibrant = DynamicScheme::vibrant(source, true, 0.5);
+        let from_variant = DynamicScheme::from_variant(source, Variant::Vibrant, true, 0.5);
+        assert_eq!(vibrant.primary(), from_variant.primary());
+        assert_eq!(vibrant.tertiary(), from_variant.tertiary());
+    }
+
+    #[test]
+    fn monochrome_palettes_are_achromatic() {
+        let scheme = DynamicScheme::monochrome(Hct::from_int(0xff0000ff), true, 0.0);
+        assert_eq!(scheme.primary(), 0xffffffff);
+        for argb in [scheme.secondary(), scheme.tertiary(), scheme.surface()] {
+            assert_eq!(red_from_argb(argb), green_from_argb(argb));
+            assert_eq!(green_from_argb(argb), blue_from_argb(argb));
+        }
+    }
+
+    #[test]
+    fn on_primary_meets_minimum_contrast() {
+        for argb in [0xff0000ff, 0xff6750a4, 0xffff0000, 0xff00ff00, 0xffffff00] {
+            for variant in VARIANTS {
+                for is_dark in [false, true] {
+                    for contrast_level in [0.0, 0.5, 1.0] {
+                        let scheme = DynamicScheme::from_variant(
+                            Hct::from_int(argb),
+                            variant,
+                            is_dark,
+                            contrast_level,
+                        );
+                        assert!(ratio_of_argb(scheme.on_primary(), scheme.primary()) >= 4.5);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn content_and_fidelity_tertiary_use_temperature() {
+        let source = Hct::from_int(0xff0000ff);
+
+        let fidelity = DynamicScheme::fidelity(source, false, 0.0);
+        let complement = TemperatureCache::new(source).complement();
+        assert!((fidelity.tertiary_palette.hue() - complement.hue()).abs() < 1e-9);
+        assert!((fidelity.tertiary_palette.chroma() - complement.chroma()).abs() < 1e-9);
+
+        let content = DynamicScheme::content(source, false, 0.0);
+        let analogous = *TemperatureCache::new(source)
+            .analogous(3, 6)
+            .last()
+            .unwrap();
+        assert!((content.tertiary_palette.hue() - analogous.hue()).abs() < 1e-9);
+    }
+}
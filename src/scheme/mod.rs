@@ -1,20 +1,41 @@
-use crate::palette::CorePalette;
+pub mod dynamic_scheme;
+
+pub use self::dynamic_scheme::DynamicScheme;
+use crate::{
+    dynamiccolor::Variant,
+    hct::{Hct, ViewingConditions},
+    palette::{CorePalette, TonalPalette},
+    utils::color_utils::lstar_from_argb,
+};
 
 /// This structure is the same concept as Flutter's ColorScheme class,
 /// inlined into libmonet to ensure parity across languages.
+#[derive(Debug, PartialEq, Eq)]
 pub struct Scheme {
     pub primary: u32,
     pub on_primary: u32,
     pub primary_container: u32,
     pub on_primary_container: u32,
+    pub primary_fixed: u32,
+    pub primary_fixed_dim: u32,
+    pub on_primary_fixed: u32,
+    pub on_primary_fixed_variant: u32,
     pub secondary: u32,
     pub on_secondary: u32,
     pub secondary_container: u32,
     pub on_secondary_container: u32,
+    pub secondary_fixed: u32,
+    pub secondary_fixed_dim: u32,
+    pub on_secondary_fixed: u32,
+    pub on_secondary_fixed_variant: u32,
     pub tertiary: u32,
     pub on_tertiary: u32,
     pub tertiary_container: u32,
     pub on_tertiary_container: u32,
+    pub tertiary_fixed: u32,
+    pub tertiary_fixed_dim: u32,
+    pub on_tertiary_fixed: u32,
+    pub on_tertiary_fixed_variant: u32,
     pub error: u32,
     pub on_error: u32,
     pub error_container: u32,
@@ -25,27 +46,50 @@ pub struct Scheme {
     pub on_surface: u32,
     pub surface_variant: u32,
     pub on_surface_variant: u32,
+    pub surface_dim: u32,
+    pub surface_bright: u32,
+    pub surface_container_lowest: u32,
+    pub surface_container_low: u32,
+    pub surface_container: u32,
+    pub surface_container_high: u32,
+    pub surface_container_highest: u32,
+    pub surface_tint: u32,
     pub outline: u32,
+    pub outline_variant: u32,
     pub shadow: u32,
+    pub scrim: u32,
     pub inverse_surface: u32,
     pub inverse_on_surface: u32,
     pub inverse_primary: u32,
 }
 
 impl Scheme {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         primary: u32,
         on_primary: u32,
         primary_container: u32,
         on_primary_container: u32,
+        primary_fixed: u32,
+        primary_fixed_dim: u32,
+        on_primary_fixed: u32,
+        on_primary_fixed_variant: u32,
         secondary: u32,
         on_secondary: u32,
         secondary_container: u32,
         on_secondary_container: u32,
+        secondary_fixed: u32,
+        secondary_fixed_dim: u32,
+        on_secondary_fixed: u32,
+        on_secondary_fixed_variant: u32,
         tertiary: u32,
         on_tertiary: u32,
         tertiary_container: u32,
         on_tertiary_container: u32,
+        tertiary_fixed: u32,
+        tertiary_fixed_dim: u32,
+        on_tertiary_fixed: u32,
+        on_tertiary_fixed_variant: u32,
         error: u32,
         on_error: u32,
         error_container: u32,
@@ -56,8 +100,18 @@ impl Scheme {
         on_surface: u32,
         surface_variant: u32,
         on_surface_variant: u32,
+        surface_dim: u32,
+        surface_bright: u32,
+        surface_container_lowest: u32,
+        surface_container_low: u32,
+        surface_container: u32,
+        surface_container_high: u32,
+        surface_container_highest: u32,
+        surface_tint: u32,
         outline: u32,
+        outline_variant: u32,
         shadow: u32,
+        scrim: u32,
         inverse_surface: u32,
         inverse_on_surface: u32,
         inverse_primary: u32,
@@ -67,14 +121,26 @@ impl Scheme {
             on_primary,
             primary_container,
             on_primary_container,
+            primary_fixed,
+            primary_fixed_dim,
+            on_primary_fixed,
+            on_primary_fixed_variant,
             secondary,
             on_secondary,
             secondary_container,
             on_secondary_container,
+            secondary_fixed,
+            secondary_fixed_dim,
+            on_secondary_fixed,
+            on_secondary_fixed_variant,
             tertiary,
             on_tertiary,
             tertiary_container,
             on_tertiary_container,
+            tertiary_fixed,
+            tertiary_fixed_dim,
+            on_tertiary_fixed,
+            on_tertiary_fixed_variant,
             error,
             on_error,
             error_container,
@@ -85,8 +151,18 @@ impl Scheme {
             on_surface,
             surface_variant,
             on_surface_variant,
+            surface_dim,
+            surface_bright,
+            surface_container_lowest,
+            surface_container_low,
+            surface_container,
+            surface_container_high,
+            surface_container_highest,
+            surface_tint,
             outline,
+            outline_variant,
             shadow,
+            scrim,
             inverse_surface,
             inverse_on_surface,
             inverse_primary,
@@ -103,104 +179,703 @@ impl Scheme {
         Scheme::dark_from_core_palette(&mut palette)
     }
 
+    /// Creates a light scheme from `color`, with hue and chroma measured in
+    /// `viewing_conditions`.
+    pub fn light_in_viewing_conditions(
+        color: u32,
+        viewing_conditions: &ViewingConditions,
+    ) -> Scheme {
+        let mut palette = CorePalette::of_in_viewing_conditions(color, viewing_conditions);
+        Scheme::light_from_core_palette(&mut palette)
+    }
+
+    /// Creates a dark scheme from `color`, with hue and chroma measured in
+    /// `viewing_conditions`.
+    pub fn dark_in_viewing_conditions(
+        color: u32,
+        viewing_conditions: &ViewingConditions,
+    ) -> Scheme {
+        let mut palette = CorePalette::of_in_viewing_conditions(color, viewing_conditions);
+        Scheme::dark_from_core_palette(&mut palette)
+    }
+
+    /// Creates a light scheme from `color` whose roles are adjusted for
+    /// `contrast_level`.
+    ///
+    /// `contrast_level` ranges from -1.0 (reduced contrast) through 0.0
+    /// (standard), 0.5 (medium) to 1.0 (high contrast). Values outside that
+    /// range are treated as the nearest bound, and NaN is treated as 0.0.
+    ///
+    /// At 0.0 the scheme is the same as [`Scheme::light`]. At other levels each role's
+    /// tone moves by as much as the role's tone in a [`DynamicScheme`] moves
+    /// from 0.0 to `contrast_level`.
+    pub fn light_with_contrast(color: u32, contrast_level: f64) -> Scheme {
+        Scheme::with_contrast(color, false, contrast_level)
+    }
+
+    /// Creates a dark scheme from `color` whose roles are adjusted for
+    /// `contrast_level`.
+    ///
+    /// `contrast_level` ranges from -1.0 (reduced contrast) through 0.0
+    /// (standard), 0.5 (medium) to 1.0 (high contrast). Values outside that
+    /// range are treated as the nearest bound, and NaN is treated as 0.0.
+    ///
+    /// At 0.0 the scheme is the same as [`Scheme::dark`]. At other levels each role's
+    /// tone moves by as much as the role's tone in a [`DynamicScheme`] moves
+    /// from 0.0 to `contrast_level`.
+    pub fn dark_with_contrast(color: u32, contrast_level: f64) -> Scheme {
+        Scheme::with_contrast(color, true, contrast_level)
+    }
+
+    /// Resolves every role of `scheme` into a [`Scheme`].
+    pub fn from_dynamic_scheme(scheme: &DynamicScheme) -> Scheme {
+        Scheme {
+            primary: scheme.primary(),
+            on_primary: scheme.on_primary(),
+            primary_container: scheme.primary_container(),
+            on_primary_container: scheme.on_primary_container(),
+            primary_fixed: scheme.primary_fixed(),
+            primary_fixed_dim: scheme.primary_fixed_dim(),
+            on_primary_fixed: scheme.on_primary_fixed(),
+            on_primary_fixed_variant: scheme.on_primary_fixed_variant(),
+            secondary: scheme.secondary(),
+            on_secondary: scheme.on_secondary(),
+            secondary_container: scheme.secondary_container(),
+            on_secondary_container: scheme.on_secondary_container(),
+            secondary_fixed: scheme.secondary_fixed(),
+            secondary_fixed_dim: scheme.secondary_fixed_dim(),
+            on_secondary_fixed: scheme.on_secondary_fixed(),
+            on_secondary_fixed_variant: scheme.on_secondary_fixed_variant(),
+            tertiary: scheme.tertiary(),
+            on_tertiary: scheme.on_tertiary(),
+            tertiary_container: scheme.tertiary_container(),
+            on_tertiary_container: scheme.on_tertiary_container(),
+            tertiary_fixed: scheme.tertiary_fixed(),
+            tertiary_fixed_dim: scheme.tertiary_fixed_dim(),
+            on_tertiary_fixed: scheme.on_tertiary_fixed(),
+            on_tertiary_fixed_variant: scheme.on_tertiary_fixed_variant(),
+            error: scheme.error(),
+            on_error: scheme.on_error(),
+            error_container: scheme.error_container(),
+            on_error_container: scheme.on_error_container(),
+            background: scheme.background(),
+            on_background: scheme.on_background(),
+            surface: scheme.surface(),
+            on_surface: scheme.on_surface(),
+            surface_variant: scheme.surface_variant(),
+            on_surface_variant: scheme.on_surface_variant(),
+            surface_dim: scheme.surface_dim(),
+            surface_bright: scheme.surface_bright(),
+            surface_container_lowest: scheme.surface_container_lowest(),
+            surface_container_low: scheme.surface_container_low(),
+            surface_container: scheme.surface_container(),
+            surface_container_high: scheme.surface_container_high(),
+            surface_container_highest: scheme.surface_container_highest(),
+            surface_tint: scheme.surface_tint(),
+            outline: scheme.outline(),
+            outline_variant: scheme.outline_variant(),
+            shadow: scheme.shadow(),
+            scrim: scheme.scrim(),
+            inverse_surface: scheme.inverse_surface(),
+            inverse_on_surface: scheme.inverse_on_surface(),
+            inverse_primary: scheme.inverse_primary(),
+        }
+    }
+
+    // The roles of DynamicScheme's standard contrast level use some tones that
+    // differ from Scheme::light and Scheme::dark, ex. T98 rather than T99 for
+    // the light surface. So that the standard level matches them, the legacy
+    // roles are moved by the change in tone between the standard and the
+    // requested level.
+    fn with_contrast(color: u32, is_dark: bool, contrast_level: f64) -> Scheme {
+        let contrast_level = if contrast_level.is_nan() {
+            0.0
+        } else {
+            contrast_level.clamp(-1.0, 1.0)
+        };
+        let legacy = if is_dark {
+            Scheme::dark(color)
+        } else {
+            Scheme::light(color)
+        };
+        if contrast_level == 0.0 {
+            return legacy;
+        }
+        let standard = Scheme::from_dynamic_scheme(&Scheme::dynamic_scheme(color, is_dark, 0.0));
+        let adjusted =
+            Scheme::from_dynamic_scheme(&Scheme::dynamic_scheme(color, is_dark, contrast_level));
+        let shift = |standard: u32, legacy: u32, adjusted: u32| {
+            if standard == adjusted {
+                return legacy;
+            }
+            let mut hct = Hct::from_int(legacy);
+            let delta = lstar_from_argb(adjusted) - lstar_from_argb(standard);
+            hct.set_tone((hct.tone() + delta).clamp(0.0, 100.0));
+            hct.to_int()
+        };
+        Scheme {
+            primary: shift(standard.primary, legacy.primary, adjusted.primary),
+            on_primary: shift(standard.on_primary, legacy.on_primary, adjusted.on_primary),
+            primary_container: shift(
+                standard.primary_container,
+                legacy.primary_container,
+                adjusted.primary_container,
+            ),
+            on_primary_container: shift(
+                standard.on_primary_container,
+                legacy.on_primary_container,
+                adjusted.on_primary_container,
+            ),
+            primary_fixed: shift(
+                standard.primary_fixed,
+                legacy.primary_fixed,
+                adjusted.primary_fixed,
+            ),
+            primary_fixed_dim: shift(
+                standard.primary_fixed_dim,
+                legacy.primary_fixed_dim,
+                adjusted.primary_fixed_dim,
+            ),
+            on_primary_fixed: shift(
+                standard.on_primary_fixed,
+                legacy.on_primary_fixed,
+                adjusted.on_primary_fixed,
+            ),
+            on_primary_fixed_variant: shift(
+                standard.on_primary_fixed_variant,
+                legacy.on_primary_fixed_variant,
+                adjusted.on_primary_fixed_variant,
+            ),
+            secondary: shift(standard.secondary, legacy.secondary, adjusted.secondary),
+            on_secondary: shift(
+                standard.on_secondary,
+                legacy.on_secondary,
+                adjusted.on_secondary,
+            ),
+            secondary_container: shift(
+                standard.secondary_container,
+                legacy.secondary_container,
+                adjusted.secondary_container,
+            ),
+            on_secondary_container: shift(
+                standard.on_secondary_container,
+                legacy.on_secondary_container,
+                adjusted.on_secondary_container,
+            ),
+            secondary_fixed: shift(
+                standard.secondary_fixed,
+                legacy.secondary_fixed,
+                adjusted.secondary_fixed,
+            ),
+            secondary_fixed_dim: shift(
+                standard.secondary_fixed_dim,
+                legacy.secondary_fixed_dim,
+                adjusted.secondary_fixed_dim,
+            ),
+            on_secondary_fixed: shift(
+                standard.on_secondary_fixed,
+                legacy.on_secondary_fixed,
+                adjusted.on_secondary_fixed,
+            ),
+            on_secondary_fixed_variant: shift(
+                standard.on_secondary_fixed_variant,
+                legacy.on_secondary_fixed_variant,
+                adjusted.on_secondary_fixed_variant,
+            ),
+            tertiary: shift(standard.tertiary, legacy.tertiary, adjusted.tertiary),
+            on_tertiary: shift(
+                standard.on_tertiary,
+                legacy.on_tertiary,
+                adjusted.on_tertiary,
+            ),
+            tertiary_container: shift(
+                standard.tertiary_container,
+                legacy.tertiary_container,
+                adjusted.tertiary_container,
+            ),
+            on_tertiary_container: shift(
+                standard.on_tertiary_container,
+                legacy.on_tertiary_container,
+                adjusted.on_tertiary_container,
+            ),
+            tertiary_fixed: shift(
+                standard.tertiary_fixed,
+                legacy.tertiary_fixed,
+                adjusted.tertiary_fixed,
+            ),
+            tertiary_fixed_dim: shift(
+                standard.tertiary_fixed_dim,
+                legacy.tertiary_fixed_dim,
+                adjusted.tertiary_fixed_dim,
+            ),
+            on_tertiary_fixed: shift(
+                standard.on_tertiary_fixed,
+                legacy.on_tertiary_fixed,
+                adjusted.on_tertiary_fixed,
+            ),
+            on_tertiary_fixed_variant: shift(
+                standard.on_tertiary_fixed_variant,
+                legacy.on_tertiary_fixed_variant,
+                adjusted.on_tertiary_fixed_variant,
+            ),
+            error: shift(standard.error, legacy.error, adjusted.error),
+            on_error: shift(standard.on_error, legacy.on_error, adjusted.on_error),
+            error_container: shift(
+                standard.error_container,
+                legacy.error_container,
+                adjusted.error_container,
+            ),
+            on_error_container: shift(
+                standard.on_error_container,
+                legacy.on_error_container,
+                adjusted.on_error_container,
+            ),
+            background: shift(standard.background, legacy.background, adjusted.background),
+            on_background: shift(
+                standard.on_background,
+                legacy.on_background,
+                adjusted.on_background,
+            ),
+            surface: shift(standard.surface, legacy.surface, adjusted.surface),
+            on_surface: shift(standard.on_surface, legacy.on_surface, adjusted.on_surface),
+            surface_variant: shift(
+                standard.surface_variant,
+                legacy.surface_variant,
+                adjusted.surface_variant,
+            ),
+            on_surface_variant: shift(
+                standard.on_surface_variant,
+                legacy.on_surface_variant,
+                adjusted.on_surface_variant,
+            ),
+            surface_dim: shift(
+                standard.surface_dim,
+                legacy.surface_dim,
+                adjusted.surface_dim,
+            ),
+            surface_bright: shift(
+                standard.surface_bright,
+                legacy.surface_bright,
+                adjusted.surface_bright,
+            ),
+            surface_container_lowest: shift(
+                standard.surface_container_lowest,
+                legacy.surface_container_lowest,
+                adjusted.surface_container_lowest,
+            ),
+            surface_container_low: shift(
+                standard.surface_container_low,
+                legacy.surface_container_low,
+                adjusted.surface_container_low,
+            ),
+            surface_container: shift(
+                standard.surface_container,
+                legacy.surface_container,
+                adjusted.surface_container,
+            ),
+            surface_container_high: shift(
+                standard.surface_container_high,
+                legacy.surface_container_high,
+                adjusted.surface_container_high,
+            ),
+            surface_container_highest: shift(
+                standard.surface_container_highest,
+                legacy.surface_container_highest,
+                adjusted.surface_container_highest,
+            ),
+            surface_tint: shift(
+                standard.surface_tint,
+                legacy.surface_tint,
+                adjusted.surface_tint,
+            ),
+            outline: shift(standard.outline, legacy.outline, adjusted.outline),
+            outline_variant: shift(
+                standard.outline_variant,
+                legacy.outline_variant,
+                adjusted.outline_variant,
+            ),
+            shadow: shift(standard.shadow, legacy.shadow, adjusted.shadow),
+            scrim: shift(standard.scrim, legacy.scrim, adjusted.scrim),
+            inverse_surface: shift(
+                standard.inverse_surface,
+                legacy.inverse_surface,
+                adjusted.inverse_surface,
+            ),
+            inverse_on_surface: shift(
+                standard.inverse_on_surface,
+                legacy.inverse_on_surface,
+                adjusted.inverse_on_surface,
+            ),
+            inverse_primary: shift(
+                standard.inverse_primary,
+                legacy.inverse_primary,
+                adjusted.inverse_primary,
+            ),
+        }
+    }
+
+    // Builds a DynamicScheme from the same palettes as CorePalette::of, so the
+    // contrast-aware constructors stay close to Scheme::light and Scheme::dark.
+    fn dynamic_scheme(color: u32, is_dark: bool, contrast_level: f64) -> DynamicScheme {
+        let CorePalette {
+            primary,
+            secondary,
+            tertiary,
+            neutral,
+            neutral_variant,
+            ..
+        } = CorePalette::of(color);
+        DynamicScheme::new(
+            Hct::from_int(color),
+            Variant::TonalSpot,
+            is_dark,
+            contrast_level,
+            primary,
+            secondary,
+            tertiary,
+            neutral,
+            neutral_variant,
+        )
+    }
+
     pub fn light_from_core_palette(palette: &mut CorePalette) -> Scheme {
         Scheme {
-            primary: palette.primary.tone(40).unwrap(),
-            on_primary: palette.primary.tone(100).unwrap(),
-            primary_container: palette.primary.tone(90).unwrap(),
-            on_primary_container: palette.primary.tone(10).unwrap(),
-            secondary: palette.secondary.tone(40).unwrap(),
-            on_secondary: palette.secondary.tone(100).unwrap(),
-            secondary_container: palette.secondary.tone(90).unwrap(),
-            on_secondary_container: palette.secondary.tone(10).unwrap(),
-            tertiary: palette.tertiary.tone(40).unwrap(),
-            on_tertiary: palette.tertiary.tone(100).unwrap(),
-            tertiary_container: palette.tertiary.tone(90).unwrap(),
-            on_tertiary_container: palette.tertiary.tone(10).unwrap(),
-            error: palette.error.tone(40).unwrap(),
-            on_error: palette.error.tone(100).unwrap(),
-            error_container: palette.error.tone(90).unwrap(),
-            on_error_container: palette.error.tone(10).unwrap(),
-            background: palette.neutral.tone(99).unwrap(),
-            on_background: palette.neutral.tone(10).unwrap(),
-            surface: palette.neutral.tone(99).unwrap(),
-            on_surface: palette.neutral.tone(10).unwrap(),
-            surface_variant: palette.neutral_variant.tone(90).unwrap(),
-            on_surface_variant: palette.neutral_variant.tone(30).unwrap(),
-            outline: palette.neutral_variant.tone(50).unwrap(),
-            shadow: palette.neutral.tone(0).unwrap(),
-            inverse_surface: palette.neutral.tone(20).unwrap(),
-            inverse_on_surface: palette.neutral.tone(95).unwrap(),
-            inverse_primary: palette.primary.tone(80).unwrap(),
+            primary: tone_of(&mut palette.primary, 40),
+            on_primary: tone_of(&mut palette.primary, 100),
+            primary_container: tone_of(&mut palette.primary, 90),
+            on_primary_container: tone_of(&mut palette.primary, 10),
+            primary_fixed: tone_of(&mut palette.primary, 90),
+            primary_fixed_dim: tone_of(&mut palette.primary, 80),
+            on_primary_fixed: tone_of(&mut palette.primary, 10),
+            on_primary_fixed_variant: tone_of(&mut palette.primary, 30),
+            secondary: tone_of(&mut palette.secondary, 40),
+            on_secondary: tone_of(&mut palette.secondary, 100),
+            secondary_container: tone_of(&mut palette.secondary, 90),
+            on_secondary_container: tone_of(&mut palette.secondary, 10),
+            secondary_fixed: tone_of(&mut palette.secondary, 90),
+            secondary_fixed_dim: tone_of(&mut palette.secondary, 80),
+            on_secondary_fixed: tone_of(&mut palette.secondary, 10),
+            on_secondary_fixed_variant: tone_of(&mut palette.secondary, 30),
+            tertiary: tone_of(&mut palette.tertiary, 40),
+            on_tertiary: tone_of(&mut palette.tertiary, 100),
+            tertiary_container: tone_of(&mut palette.tertiary, 90),
+            on_tertiary_container: tone_of(&mut palette.tertiary, 10),
+            tertiary_fixed: tone_of(&mut palette.tertiary, 90),
+            tertiary_fixed_dim: tone_of(&mut palette.tertiary, 80),
+            on_tertiary_fixed: tone_of(&mut palette.tertiary, 10),
+            on_tertiary_fixed_variant: tone_of(&mut palette.tertiary, 30),
+            error: tone_of(&mut palette.error, 40),
+            on_error: tone_of(&mut palette.error, 100),
+            error_container: tone_of(&mut palette.error, 90),
+            on_error_container: tone_of(&mut palette.error, 10),
+            background: tone_of(&mut palette.neutral, 99),
+            on_background: tone_of(&mut palette.neutral, 10),
+            surface: tone_of(&mut palette.neutral, 99),
+            on_surface: tone_of(&mut palette.neutral, 10),
+            surface_variant: tone_of(&mut palette.neutral_variant, 90),
+            on_surface_variant: tone_of(&mut palette.neutral_variant, 30),
+            surface_dim: tone_of(&mut palette.neutral, 87),
+            surface_bright: tone_of(&mut palette.neutral, 98),
+            surface_container_lowest: tone_of(&mut palette.neutral, 100),
+            surface_container_low: tone_of(&mut palette.neutral, 96),
+            surface_container: tone_of(&mut palette.neutral, 94),
+            surface_container_high: tone_of(&mut palette.neutral, 92),
+            surface_container_highest: tone_of(&mut palette.neutral, 90),
+            surface_tint: tone_of(&mut palette.primary, 40),
+            outline: tone_of(&mut palette.neutral_variant, 50),
+            outline_variant: tone_of(&mut palette.neutral_variant, 80),
+            shadow: tone_of(&mut palette.neutral, 0),
+            scrim: tone_of(&mut palette.neutral, 0),
+            inverse_surface: tone_of(&mut palette.neutral, 20),
+            inverse_on_surface: tone_of(&mut palette.neutral, 95),
+            inverse_primary: tone_of(&mut palette.primary, 80),
         }
     }
 
     pub fn dark_from_core_palette(palette: &mut CorePalette) -> Scheme {
         Scheme {
-            primary: palette.primary.tone(80).unwrap(),
-            on_primary: palette.primary.tone(20).unwrap(),
-            primary_container: palette.primary.tone(30).unwrap(),
-            on_primary_container: palette.primary.tone(90).unwrap(),
-            secondary: palette.secondary.tone(80).unwrap(),
-            on_secondary: palette.secondary.tone(20).unwrap(),
-            secondary_container: palette.secondary.tone(30).unwrap(),
-            on_secondary_container: palette.secondary.tone(90).unwrap(),
-            tertiary: palette.tertiary.tone(80).unwrap(),
-            on_tertiary: palette.tertiary.tone(20).unwrap(),
-            tertiary_container: palette.tertiary.tone(30).unwrap(),
-            on_tertiary_container: palette.tertiary.tone(90).unwrap(),
-            error: palette.error.tone(80).unwrap(),
-            on_error: palette.error.tone(20).unwrap(),
-            error_container: palette.error.tone(30).unwrap(),
-            on_error_container: palette.error.tone(80).unwrap(),
-            background: palette.neutral.tone(10).unwrap(),
-            on_background: palette.neutral.tone(90).unwrap(),
-            surface: palette.neutral.tone(10).unwrap(),
-            on_surface: palette.neutral.tone(90).unwrap(),
-            surface_variant: palette.neutral_variant.tone(30).unwrap(),
-            on_surface_variant: palette.neutral_variant.tone(80).unwrap(),
-            outline: palette.neutral_variant.tone(60).unwrap(),
-            shadow: palette.neutral.tone(0).unwrap(),
-            inverse_surface: palette.neutral.tone(90).unwrap(),
-            inverse_on_surface: palette.neutral.tone(20).unwrap(),
-            inverse_primary: palette.primary.tone(40).unwrap(),
+            primary: tone_of(&mut palette.primary, 80),
+            on_primary: tone_of(&mut palette.primary, 20),
+            primary_container: tone_of(&mut palette.primary, 30),
+            on_primary_container: tone_of(&mut palette.primary, 90),
+            primary_fixed: tone_of(&mut palette.primary, 90),
+            primary_fixed_dim: tone_of(&mut palette.primary, 80),
+            on_primary_fixed: tone_of(&mut palette.primary, 10),
+            on_primary_fixed_variant: tone_of(&mut palette.primary, 30),
+            secondary: tone_of(&mut palette.secondary, 80),
+            on_secondary: tone_of(&mut palette.secondary, 20),
+            secondary_container: tone_of(&mut palette.secondary, 30),
+            on_secondary_container: tone_of(&mut palette.secondary, 90),
+            secondary_fixed: tone_of(&mut palette.secondary, 90),
+            secondary_fixed_dim: tone_of(&mut palette.secondary, 80),
+            on_secondary_fixed: tone_of(&mut palette.secondary, 10),
+            on_secondary_fixed_variant: tone_of(&mut palette.secondary, 30),
+            tertiary: tone_of(&mut palette.tertiary, 80),
+            on_tertiary: tone_of(&mut palette.tertiary, 20),
+            tertiary_container: tone_of(&mut palette.tertiary, 30),
+            on_tertiary_container: tone_of(&mut palette.tertiary, 90),
+            tertiary_fixed: tone_of(&mut palette.tertiary, 90),
+            tertiary_fixed_dim: tone_of(&mut palette.tertiary, 80),
+            on_tertiary_fixed: tone_of(&mut palette.tertiary, 10),
+            on_tertiary_fixed_variant: tone_of(&mut palette.tertiary, 30),
+            error: tone_of(&mut palette.error, 80),
+            on_error: tone_of(&mut palette.error, 20),
+            error_container: tone_of(&mut palette.error, 30),
+            on_error_container: tone_of(&mut palette.error, 80),
+            background: tone_of(&mut palette.neutral, 10),
+            on_background: tone_of(&mut palette.neutral, 90),
+            surface: tone_of(&mut palette.neutral, 10),
+            on_surface: tone_of(&mut palette.neutral, 90),
+            surface_variant: tone_of(&mut palette.neutral_variant, 30),
+            on_surface_variant: tone_of(&mut palette.neutral_variant, 80),
+            surface_dim: tone_of(&mut palette.neutral, 6),
+            surface_bright: tone_of(&mut palette.neutral, 24),
+            surface_container_lowest: tone_of(&mut palette.neutral, 4),
+            surface_container_low: tone_of(&mut palette.neutral, 10),
+            surface_container: tone_of(&mut palette.neutral, 12),
+            surface_container_high: tone_of(&mut palette.neutral, 17),
+            surface_container_highest: tone_of(&mut palette.neutral, 22),
+            surface_tint: tone_of(&mut palette.primary, 80),
+            outline: tone_of(&mut palette.neutral_variant, 60),
+            outline_variant: tone_of(&mut palette.neutral_variant, 30),
+            shadow: tone_of(&mut palette.neutral, 0),
+            scrim: tone_of(&mut palette.neutral, 0),
+            inverse_surface: tone_of(&mut palette.neutral, 90),
+            inverse_on_surface: tone_of(&mut palette.neutral, 20),
+            inverse_primary: tone_of(&mut palette.primary, 40),
         }
     }
 }
 
+// Tones outside TonalPalette::COMMON_TONES aren't stored in palettes created
+// with TonalPalette::from_list, so those are computed from the palette's
+// approximate hue and chroma instead.
+fn tone_of(palette: &mut TonalPalette, tone: u32) -> u32 {
+    palette
+        .tone(tone)
+        .unwrap_or_else(|_| palette.get_hct(tone as f64).to_int())
+}
+
 #[cfg(test)]
 mod test {
-    use crate::scheme::Scheme;
+    use crate::{
+        hct::{Hct, ViewingConditions},
+        palette::CorePalette,
+        scheme::Scheme,
+        utils::{color_utils::lstar_from_argb, contrast::ratio_of_argb},
+    };
+
+    const CONTRAST_LEVELS: [f64; 5] = [-1.0, -0.5, 0.0, 0.5, 1.0];
 
     #[test]
     fn blue_light_scheme() {
         let scheme = Scheme::light(0xff0000ff);
-        assert_eq!(scheme.primary, 0xff343DFF);
+        assert_eq!(scheme.primary, 0xff343dff);
     }
 
     #[test]
     fn blue_dark_scheme() {
         let scheme = Scheme::dark(0xff0000ff);
-        assert_eq!(scheme.primary, 0xffBEC2FF);
+        assert_eq!(scheme.primary, 0xffbec2ff);
     }
 
     #[test]
     fn third_party_light_scheme() {
-        let scheme = Scheme::light(0xff6750A4);
-        assert_eq!(scheme.primary, 0xff6750A4);
-        assert_eq!(scheme.secondary, 0xff625B71);
-        assert_eq!(scheme.tertiary, 0xff7E5260);
-        assert_eq!(scheme.surface, 0xffFFFBFF);
-        assert_eq!(scheme.on_surface, 0xff1C1B1E);
+        let scheme = Scheme::light(0xff6750a4);
+        assert_eq!(scheme.primary, 0xff6750a4);
+        assert_eq!(scheme.secondary, 0xff625b71);
+        assert_eq!(scheme.tertiary, 0xff7e5260);
+        assert_eq!(scheme.surface, 0xfffffbff);
+        assert_eq!(scheme.on_surface, 0xff1c1b1e);
     }
 
     #[test]
     fn third_party_dark_scheme() {
-        let scheme = Scheme::dark(0xff6750A4);
-        assert_eq!(scheme.primary, 0xffCFBCFF);
-        assert_eq!(scheme.secondary, 0xffCBC2DB);
-        assert_eq!(scheme.tertiary, 0xffEFB8C8);
+        let scheme = Scheme::dark(0xff6750a4);
+        assert_eq!(scheme.primary, 0xffcfbcff);
+        assert_eq!(scheme.secondary, 0xffcbc2db);
+        assert_eq!(scheme.tertiary, 0xffefb8c8);
         assert_eq!(scheme.surface, 0xff1c1b1e);
-        assert_eq!(scheme.on_surface, 0xffE6E1E6);
+        assert_eq!(scheme.on_surface, 0xffe6e1e6);
+    }
+
+    #[test]
+    fn on_primary_meets_minimum_contrast() {
+        for argb in [0xff0000ff, 0xff6750a4, 0xffff0000, 0xff00ff00, 0xffffff00] {
+            for scheme in [Scheme::light(argb), Scheme::dark(argb)] {
+                assert!(ratio_of_argb(scheme.on_primary, scheme.primary) >= 4.5);
+            }
+        }
+    }
+
+    #[test]
+    fn contrast_level_raises_text_contrast() {
+        for argb in [0xff0000ff, 0xff6750a4, 0xffff0000, 0xff00ff00, 0xffffff00] {
+            for is_dark in [false, true] {
+                let schemes: Vec<Scheme> = CONTRAST_LEVELS
+                    .iter()
+                    .map(|&level| {
+                        if is_dark {
+                            Scheme::dark_with_contrast(argb, level)
+                        } else {
+                            Scheme::light_with_contrast(argb, level)
+                        }
+                    })
+                    .collect();
+                for pair in schemes.windows(2) {
+                    assert!(
+                        ratio_of_argb(pair[1].on_primary, pair[1].primary)
+                            >= ratio_of_argb(pair[0].on_primary, pair[0].primary) - 0.1
+                    );
+                    assert!(
+                        ratio_of_argb(pair[1].on_surface, pair[1].surface)
+                            >= ratio_of_argb(pair[0].on_surface, pair[0].surface) - 0.1
+                    );
+                }
+                let standard = &schemes[2];
+                assert!(ratio_of_argb(standard.on_primary, standard.primary) >= 4.5);
+                assert!(ratio_of_argb(standard.on_surface, standard.surface) >= 4.5);
+                let high = &schemes[4];
+                assert!(ratio_of_argb(high.on_primary, high.primary) >= 7.0);
+                assert!(ratio_of_argb(high.on_surface, high.surface) >= 7.0);
+            }
+        }
+    }
+
+    #[test]
+    fn standard_contrast_matches_light_and_dark() {
+        for argb in [0xff4285f4, 0xff6750a4, 0xff0000ff, 0xff00ff00] {
+            let light = Scheme::light(argb);
+            let dark = Scheme::dark(argb);
+            assert_eq!(Scheme::light_with_contrast(argb, 0.0), light);
+            assert_eq!(Scheme::dark_with_contrast(argb, 0.0), dark);
+
+            // Roles move gradually away from the standard level.
+            let near_light = Scheme::light_with_contrast(argb, 0.01);
+            let near_dark = Scheme::dark_with_contrast(argb, 0.01);
+            for (near, standard) in [
+                (near_light.surface, light.surface),
+                (near_light.on_primary_container, light.on_primary_container),
+                (near_dark.surface, dark.surface),
+                (near_dark.on_error_container, dark.on_error_container),
+            ] {
+                assert!((lstar_from_argb(near) - lstar_from_argb(standard)).abs() < 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn contrast_level_is_clamped() {
+        let clamped = Scheme::light_with_contrast(0xff0000ff, 3.0);
+        let high = Scheme::light_with_contrast(0xff0000ff, 1.0);
+        assert_eq!(clamped.primary, high.primary);
+        assert_eq!(clamped.on_surface, high.on_surface);
+
+        let nan = Scheme::dark_with_contrast(0xff0000ff, f64::NAN);
+        let standard = Scheme::dark_with_contrast(0xff0000ff, 0.0);
+        assert_eq!(nan.primary, standard.primary);
+        assert_eq!(nan.on_surface, standard.on_surface);
+    }
+
+    #[test]
+    fn fixed_roles_match_across_light_and_dark() {
+        let light = Scheme::light(0xff6750a4);
+        let dark = Scheme::dark(0xff6750a4);
+        assert_eq!(light.primary_fixed, dark.primary_fixed);
+        assert_eq!(light.primary_fixed_dim, dark.primary_fixed_dim);
+        assert_eq!(light.on_primary_fixed, dark.on_primary_fixed);
+        assert_eq!(
+            light.on_primary_fixed_variant,
+            dark.on_primary_fixed_variant
+        );
+        assert_eq!(light.secondary_fixed, dark.secondary_fixed);
+        assert_eq!(
+            light.on_secondary_fixed_variant,
+            dark.on_secondary_fixed_variant
+        );
+        assert_eq!(light.tertiary_fixed_dim, dark.tertiary_fixed_dim);
+        assert_eq!(light.on_tertiary_fixed, dark.on_tertiary_fixed);
+
+        let mut palette = CorePalette::of(0xff6750a4);
+        assert_eq!(light.primary_fixed, palette.primary.tone(90).unwrap());
+        assert_eq!(
+            light.on_tertiary_fixed_variant,
+            palette.tertiary.tone(30).unwrap()
+        );
+    }
+
+    #[test]
+    fn surface_containers_are_ordered() {
+        let light = Scheme::light(0xff6750a4);
+        let light_tones: Vec<f64> = [
+            light.surface_container_lowest,
+            light.surface_container_low,
+            light.surface_container,
+            light.surface_container_high,
+            light.surface_container_highest,
+        ]
+        .iter()
+        .map(|&argb| lstar_from_argb(argb))
+        .collect();
+        assert!(light_tones.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(lstar_from_argb(light.surface_bright) > lstar_from_argb(light.surface_dim));
+
+        let dark = Scheme::dark(0xff6750a4);
+        let dark_tones: Vec<f64> = [
+            dark.surface_container_lowest,
+            dark.surface_container_low,
+            dark.surface_container,
+            dark.surface_container_high,
+            dark.surface_container_highest,
+        ]
+        .iter()
+        .map(|&argb| lstar_from_argb(argb))
+        .collect();
+        assert!(dark_tones.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(lstar_from_argb(dark.surface_bright) > lstar_from_argb(dark.surface_dim));
+        assert_eq!(dark.surface_tint, dark.primary);
+        assert_eq!(light.surface_tint, light.primary);
+    }
+
+    #[test]
+    fn from_list_palette_scheme() {
+        let mut palette = CorePalette::of(0xff6750a4);
+        let mut from_list = CorePalette::from_list(&palette.as_list());
+
+        let light = Scheme::light_from_core_palette(&mut from_list);
+        assert_eq!(
+            light.primary,
+            Scheme::light_from_core_palette(&mut palette).primary
+        );
+        assert!((lstar_from_argb(light.surface_container) - 94.0).abs() < 1.0);
+        assert!(lstar_from_argb(light.surface_bright) > lstar_from_argb(light.surface_dim));
+
+        let dark = Scheme::dark_from_core_palette(&mut from_list);
+        assert_eq!(
+            dark.primary,
+            Scheme::dark_from_core_palette(&mut palette).primary
+        );
+        assert!((lstar_from_argb(dark.surface_container) - 12.0).abs() < 1.0);
+        assert!(lstar_from_argb(dark.surface_bright) > lstar_from_argb(dark.surface_dim));
+    }
+
+    #[test]
+    fn viewing_conditions_change_scheme() {
+        let default = ViewingConditions::default();
+        let dim = ViewingConditions::new(
+            default.white_point,
+            default.adapting_luminance,
+            default.background_lstar,
+            1.0,
+            default.discounting_illuminant,
+        );
+
+        let scheme = Scheme::light_in_viewing_conditions(0xff6750a4, &default);
+        assert_eq!(scheme.primary, Scheme::light(0xff6750a4).primary);
+
+        let scheme = Scheme::dark_in_viewing_conditions(0xff6750a4, &dim);
+        assert_ne!(scheme.primary, Scheme::dark(0xff6750a4).primary);
+        let primary = Hct::from_int_in_viewing_conditions(scheme.primary, &dim);
+        assert!((primary.tone() - 80.0).abs() < 0.5);
     }
 }
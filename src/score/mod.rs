@@ -1,3 +1,4 @@
+use crate::dislike::DislikeAnalyzer;
 use crate::hct::Hct;
 use crate::utils::math_utils::{calculate_difference_degrees, sanitize_degrees_int};
 use indexmap::IndexMap;
@@ -52,7 +53,7 @@ const CUT_OFF_EXCITED_PROPORTION: f64 = 0.01;
 /// Enables use of a high cluster count for image quantization, thus ensuring
 /// colors aren't muddied, while curating the high cluster count to a much
 ///  smaller number of appropriate choices.
-
+///
 /// Given a map with keys of colors and values of how often the color appears,
 /// rank the colors based on suitability for being used for a UI theme.
 ///
@@ -65,7 +66,9 @@ const CUT_OFF_EXCITED_PROPORTION: f64 = 0.01;
 /// Google Blue will be provided as a default fallback color. The default
 /// number of colors returned is 4, simply because thats the # of colors
 /// display in Android 12's wallpaper picker.
-
+///
+/// Colors that are universally disliked, see [`DislikeAnalyzer`], are
+/// lightened before being returned.
 pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: bool) -> Vec<u32> {
     let mut population_sum = 0.0;
 
@@ -85,7 +88,7 @@ pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: b
         argb_to_raw_proportion.insert(*color, proportion);
 
         let hct = Hct::from_int(*color);
-        let hue = hct.hue.floor();
+        let hue = hct.hue().floor();
         argb_to_hct.insert(*color, hct);
 
         hue_proportions[hue as usize] += proportion;
@@ -95,7 +98,7 @@ pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: b
     // proportions around each color's hue.
     let mut argb_to_hue_proportion: IndexMap<u32, f64> = IndexMap::new();
     for (color, hct) in &argb_to_hct {
-        let hue = hct.hue.round() as i16;
+        let hue = hct.hue().round() as i16;
 
         let mut excited_proportion = 0.0;
         let mut i = hue - 15;
@@ -123,12 +126,12 @@ pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: b
 
         let proportion_score = proportion * 100.0 * WEIGHT_PROPORTION;
 
-        let chroma_weight = if cam.chroma < TARGET_CHROMA {
+        let chroma_weight = if cam.chroma() < TARGET_CHROMA {
             WEIGHT_CHROMA_BELOW
         } else {
             WEIGHT_CHROMA_ABOVE
         };
-        let chroma_score = (cam.chroma - TARGET_CHROMA) * chroma_weight;
+        let chroma_score = (cam.chroma() - TARGET_CHROMA) * chroma_weight;
 
         let score = proportion_score + chroma_score;
         argb_to_score.insert(color, score);
@@ -138,11 +141,8 @@ pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: b
         .iter()
         .map(|entry| (*entry.0, *entry.1))
         .collect();
-    println!("argb_and_score_sorted is {:?}", argb_and_score_sorted);
     argb_and_score_sorted.sort_by(|a, b| (b.1).partial_cmp(&a.1).unwrap());
-    println!("argb_and_score_sorted is now {:?}", argb_and_score_sorted);
     let argbs_score_sorted: Vec<u32> = argb_and_score_sorted.iter().map(|e| e.0).collect();
-    println!("argbs_score_sorted is again now {:?}", argbs_score_sorted);
 
     let mut final_colors_to_score: IndexMap<u32, f64> = IndexMap::new();
     let mut difference_degrees = 90.0;
@@ -153,7 +153,7 @@ pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: b
             let cam = argb_to_hct.get(color).unwrap();
             for already_chosen_color in final_colors_to_score.keys() {
                 let already_chosen_cam = argb_to_hct.get(already_chosen_color).unwrap();
-                if calculate_difference_degrees(cam.hue, already_chosen_cam.hue)
+                if calculate_difference_degrees(cam.hue(), already_chosen_cam.hue())
                     < difference_degrees
                 {
                     duplicate_hue = true;
@@ -163,13 +163,15 @@ pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: b
             if !duplicate_hue {
                 final_colors_to_score.insert(*color, *argb_to_score.get(color).unwrap());
             }
+            if final_colors_to_score.len() >= desired {
+                break;
+            }
         }
         if final_colors_to_score.len() >= desired {
             break;
         }
         difference_degrees -= 1.0;
     }
-    println!("final_colors_to_score is {:?}", final_colors_to_score);
 
     // Ensure the list of colors returned is sorted such that the first in the
     // list is the most suitable, and the last is the least suitable.
@@ -177,18 +179,15 @@ pub fn score(colors_to_population: IndexMap<u32, u32>, desired: usize, filter: b
         .iter()
         .map(|entry| ArgbAndScore::new(*entry.0, *entry.1))
         .collect();
-    println!(
-        "colors_by_score_descending is {:?}",
-        colors_by_score_descending
-    );
-    /*colors_by_score_descending.sort_unstable();
-    println!("colors_by_score_descending is now {:?}", colors_by_score_descending);*/
 
     // Ensure that at least one color is returned.
     if colors_by_score_descending.is_empty() {
         return vec![0xff4285f4]; // Google Blue
     }
-    colors_by_score_descending.iter().map(|e| e.argb).collect()
+    colors_by_score_descending
+        .iter()
+        .map(|e| DislikeAnalyzer::fix_if_disliked(argb_to_hct[&e.argb]).to_int())
+        .collect()
 }
 
 fn run_filter(
@@ -200,10 +199,8 @@ fn run_filter(
     for (color, hct) in argb_to_hct {
         let proportion = *colors_to_excited_proportion.get(color).unwrap();
 
-        if hct.chroma >= CUT_OFF_CHROMA && proportion > CUT_OFF_EXCITED_PROPORTION {
+        if hct.chroma() >= CUT_OFF_CHROMA && proportion > CUT_OFF_EXCITED_PROPORTION {
             filtered.push(*color);
-        } else {
-            println!("rejecting color {}", color);
         }
     }
     filtered
@@ -213,6 +210,8 @@ fn run_filter(
 mod test {
     use indexmap::IndexMap;
 
+    use crate::{dislike::DislikeAnalyzer, hct::Hct};
+
     use super::score;
 
     #[test]
@@ -236,7 +235,7 @@ mod test {
 
         let ranked = score(colors_to_population, 4, true);
 
-        assert_eq!(ranked[0], 0xff4285F4);
+        assert_eq!(ranked[0], 0xff4285f4);
     }
 
     #[test]
@@ -256,12 +255,37 @@ mod test {
         let mut colors_to_population = IndexMap::new();
         colors_to_population.insert(0xff008772, 1);
         colors_to_population.insert(0xff008587, 1);
-        colors_to_population.insert(0xff007EBC, 1);
+        colors_to_population.insert(0xff007ebc, 1);
 
         let ranked = score(colors_to_population, 2, true);
 
         assert_eq!(ranked.len(), 2);
-        assert_eq!(ranked[0], 0xff007EBC);
+        assert_eq!(ranked[0], 0xff007ebc);
         assert_eq!(ranked[1], 0xff008772);
     }
+
+    #[test]
+    fn returns_at_most_desired() {
+        let mut colors_to_population = IndexMap::new();
+        for i in 0..12 {
+            let hct = Hct::new(i as f64 * 30.0, 60.0, 50.0);
+            colors_to_population.insert(hct.to_int(), 1);
+        }
+
+        let ranked = score(colors_to_population, 2, true);
+
+        assert_eq!(ranked.len(), 2);
+    }
+
+    #[test]
+    fn fixes_disliked_colors() {
+        let mut colors_to_population = IndexMap::new();
+        colors_to_population.insert(0xff95884b, 1);
+
+        let ranked = score(colors_to_population, 4, true);
+
+        assert_eq!(ranked.len(), 1);
+        assert_ne!(ranked[0], 0xff95884b);
+        assert!(!DislikeAnalyzer::is_disliked(Hct::from_int(ranked[0])));
+    }
 }
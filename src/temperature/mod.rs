@@ -0,0 +1,415 @@
+use std::f64::consts::PI;
+
+use indexmap::IndexMap;
+
+use crate::{
+    hct::Hct,
+    utils::{
+        color_utils::lab_from_argb,
+        math_utils::{sanitize_degrees_double, sanitize_degrees_int},
+    },
+};
+
+/// Design utilities using color temperature theory.
+///
+/// Analogous colors, complementary color, and cache to efficiently, lazily,
+/// generate data for calculations when needed.
+pub struct TemperatureCache {
+    input: Hct,
+    precomputed_complement: Option<Hct>,
+    precomputed_hcts_by_temp: Option<Vec<Hct>>,
+    precomputed_hcts_by_hue: Option<Vec<Hct>>,
+    precomputed_temps_by_hct: Option<IndexMap<u32, f64>>,
+}
+
+impl TemperatureCache {
+    pub fn new(input: Hct) -> TemperatureCache {
+        TemperatureCache {
+            input,
+            precomputed_complement: None,
+            precomputed_hcts_by_temp: None,
+            precomputed_hcts_by_hue: None,
+            precomputed_temps_by_hct: None,
+        }
+    }
+
+    /// The color the cache was created from.
+    pub fn input(&self) -> Hct {
+        self.input
+    }
+
+    /// HCTs for all hues, with the same chroma/tone as the input.
+    /// Sorted from coldest first to warmest last.
+    pub fn hcts_by_temp(&mut self) -> &[Hct] {
+        if self.precomputed_hcts_by_temp.is_none() {
+            let mut hcts = self.hcts_by_hue().to_vec();
+            hcts.push(self.input);
+            let temperatures_by_hct = self.temps_by_hct();
+            hcts.sort_by(|a, b| {
+                temperatures_by_hct[&a.to_int()].total_cmp(&temperatures_by_hct[&b.to_int()])
+            });
+            self.precomputed_hcts_by_temp = Some(hcts);
+        }
+        self.precomputed_hcts_by_temp.as_ref().unwrap()
+    }
+
+    /// The warmest color with the same chroma and tone as the input.
+    pub fn warmest(&mut self) -> Hct {
+        *self.hcts_by_temp().last().unwrap()
+    }
+
+    /// The coldest color with the same chroma and tone as the input.
+    pub fn coldest(&mut self) -> Hct {
+        self.hcts_by_temp()[0]
+    }
+
+    /// A set of colors with differing hues, equidistant in temperature.
+    ///
+    /// In art, this is usually described as a set of 5 colors on a color wheel
+    /// divided into 12 sections. This method allows provision of either of
+    /// those values.
+    ///
+    /// Returns no colors when `count` is 0. A `divisions` of 0 is treated as
+    /// 1. When `divisions` < `count`, colors repeat.
+    ///
+    /// `count` The number of colors to return, includes the input color.
+    /// `divisions` The number of divisions on the color wheel.
+    pub fn analogous(&mut self, count: usize, divisions: usize) -> Vec<Hct> {
+        if count == 0 {
+            return vec![];
+        }
+        let divisions = divisions.max(1);
+        let start_hue = self.input.hue().round() as i16;
+        let hcts_by_hue = self.hcts_by_hue().to_vec();
+        let start_hct = hcts_by_hue[start_hue as usize];
+        let mut last_temp = self.relative_temperature(start_hct);
+        let mut all_colors = vec![start_hct];
+
+        let mut absolute_total_temp_delta = 0.0;
+        for i in 0..360 {
+            let hue = sanitize_degrees_int(start_hue + i);
+            let hct = hcts_by_hue[hue as usize];
+            let temp = self.relative_temperature(hct);
+            let temp_delta = (temp - last_temp).abs();
+            last_temp = temp;
+            absolute_total_temp_delta += temp_delta;
+        }
+        let mut hue_addend = 1;
+        let temp_step = absolute_total_temp_delta / divisions as f64;
+        let mut total_temp_delta = 0.0;
+        last_temp = self.relative_temperature(start_hct);
+        while all_colors.len() < divisions {
+            let hue = sanitize_degrees_int(start_hue + hue_addend);
+            let hct = hcts_by_hue[hue as usize];
+            let temp = self.relative_temperature(hct);
+            let temp_delta = (temp - last_temp).abs();
+            total_temp_delta += temp_delta;
+
+            let desired_total_temp_delta_for_index = all_colors.len() as f64 * temp_step;
+            let mut index_satisfied = total_temp_delta >= desired_total_temp_delta_for_index;
+            let mut index_addend = 1;
+            // Keep adding this hue to the answers until its temperature is
+            // insufficient. This ensures consistent behavior when there aren't
+            // `divisions` discrete steps between 0 and 360 in hue with
+            // `temp_step` delta in temperature between them.
+            //
+            // For example, white and black have no analogues: there are no
+            // other colors at T100/T0. Therefore, they should just be added to
+            // the array as answers.
+            while index_satisfied && all_colors.len() < divisions {
+                all_colors.push(hct);
+                let desired_total_temp_delta_for_index =
+                    (all_colors.len() + index_addend) as f64 * temp_step;
+                index_satisfied = total_temp_delta >= desired_total_temp_delta_for_index;
+                index_addend += 1;
+            }
+            last_temp = temp;
+            hue_addend += 1;
+            if hue_addend > 360 {
+                while all_colors.len() < divisions {
+                    all_colors.push(hct);
+                }
+                break;
+            }
+        }
+
+        let mut answers = vec![self.input];
+
+        // First, generate analogues from rotating counter-clockwise.
+        let increase_hue_count = (count - 1) / 2;
+        for i in 1..(increase_hue_count + 1) {
+            let index = (all_colors.len() - i % all_colors.len()) % all_colors.len();
+            answers.insert(0, all_colors[index]);
+        }
+
+        // Second, generate analogues from rotating clockwise.
+        let decrease_hue_count = count - increase_hue_count - 1;
+        for i in 1..(decrease_hue_count + 1) {
+            let index = i % all_colors.len();
+            answers.push(all_colors[index]);
+        }
+
+        answers
+    }
+
+    /// A color that complements the input color aesthetically.
+    ///
+    /// In art, this is usually described as being across the color wheel.
+    /// History of this shows intent as a color that is just as cool-warm as the
+    /// input color is warm-cool.
+    pub fn complement(&mut self) -> Hct {
+        if let Some(complement) = self.precomputed_complement {
+            return complement;
+        }
+
+        let coldest = self.coldest();
+        let warmest = self.warmest();
+        let complement_relative_temp = 1.0 - self.input_relative_temperature();
+        let input_hue = self.input.hue();
+        // Populates both caches, which are then borrowed side by side.
+        self.temps_by_hct();
+        let temps_by_hct = self.precomputed_temps_by_hct.as_ref().unwrap();
+        let hcts_by_hue = self.precomputed_hcts_by_hue.as_ref().unwrap();
+        let coldest_hue = coldest.hue();
+        let coldest_temp = temps_by_hct[&coldest.to_int()];
+
+        let warmest_hue = warmest.hue();
+        let warmest_temp = temps_by_hct[&warmest.to_int()];
+        let range = warmest_temp - coldest_temp;
+        let start_hue_is_coldest_to_warmest =
+            TemperatureCache::is_between(input_hue, coldest_hue, warmest_hue);
+        let start_hue = if start_hue_is_coldest_to_warmest {
+            warmest_hue
+        } else {
+            coldest_hue
+        };
+        let end_hue = if start_hue_is_coldest_to_warmest {
+            coldest_hue
+        } else {
+            warmest_hue
+        };
+        let direction_of_rotation = 1.0;
+        let mut smallest_error = 1000.0;
+        let mut answer = hcts_by_hue[input_hue.round() as usize];
+
+        // Find the color in the other section, closest to the inverse percentile
+        // of the input color. This is the complement.
+        let mut hue_addend = 0.0;
+        while hue_addend <= 360.0 {
+            let hue = sanitize_degrees_double(start_hue + direction_of_rotation * hue_addend);
+            hue_addend += 1.0;
+            if !TemperatureCache::is_between(hue, start_hue, end_hue) {
+                continue;
+            }
+            let possible_answer = hcts_by_hue[hue.round() as usize];
+            let relative_temp = (temps_by_hct[&possible_answer.to_int()] - coldest_temp) / range;
+            let error = (complement_relative_temp - relative_temp).abs();
+            if error < smallest_error {
+                smallest_error = error;
+                answer = possible_answer;
+            }
+        }
+        self.precomputed_complement = Some(answer);
+        answer
+    }
+
+    /// Temperature relative to all colors with the same chroma and tone.
+    /// Value on a scale from 0 to 1.
+    pub fn relative_temperature(&mut self, hct: Hct) -> f64 {
+        let coldest = self.coldest();
+        let warmest = self.warmest();
+        let temps_by_hct = self.temps_by_hct();
+        let range = temps_by_hct[&warmest.to_int()] - temps_by_hct[&coldest.to_int()];
+        let difference_from_coldest =
+            TemperatureCache::temperature_of(temps_by_hct, hct) - temps_by_hct[&coldest.to_int()];
+        // Handle when there's no difference in temperature between warmest and
+        // coldest: for example, at T100, only one color is available, white.
+        if range == 0.0 {
+            return 0.5;
+        }
+        difference_from_coldest / range
+    }
+
+    /// Relative temperature of the input color. See
+    /// [`TemperatureCache::relative_temperature`].
+    pub fn input_relative_temperature(&mut self) -> f64 {
+        self.relative_temperature(self.input)
+    }
+
+    /// A map with keys of HCTs in [`TemperatureCache::hcts_by_temp`], as ARGB
+    /// integers, and values of raw temperature.
+    pub fn temps_by_hct(&mut self) -> &IndexMap<u32, f64> {
+        if self.precomputed_temps_by_hct.is_none() {
+            let input = self.input;
+            let mut temperatures_by_hct = IndexMap::new();
+            for hct in self.hcts_by_hue().iter().chain([&input]) {
+                temperatures_by_hct.insert(hct.to_int(), TemperatureCache::raw_temperature(*hct));
+            }
+            self.precomputed_temps_by_hct = Some(temperatures_by_hct);
+        }
+        self.precomputed_temps_by_hct.as_ref().unwrap()
+    }
+
+    /// HCTs for all hues, with the same chroma/tone as the input.
+    /// Sorted ascending, hue 0 to 360.
+    pub fn hcts_by_hue(&mut self) -> &[Hct] {
+        if self.precomputed_hcts_by_hue.is_none() {
+            let hcts = (0..=360)
+                .map(|hue| {
+                    Hct::new_in_viewing_conditions(
+                        hue as f64,
+                        self.input.chroma(),
+                        self.input.tone(),
+                        self.input.viewing_conditions(),
+                    )
+                })
+                .collect();
+            self.precomputed_hcts_by_hue = Some(hcts);
+        }
+        self.precomputed_hcts_by_hue.as_ref().unwrap()
+    }
+
+    /// Determines if an angle is between two other angles, rotating clockwise.
+    pub fn is_between(angle: f64, a: f64, b: f64) -> bool {
+        if a < b {
+            return a <= angle && angle <= b;
+        }
+        a <= angle || angle <= b
+    }
+
+    /// Value representing cool-warm factor of a color.
+    /// Values below 0 are considered cool, above, warm.
+    ///
+    /// Color science has researched emotion and harmony, which art uses to
+    /// select colors. Warm-cool is the foundation of analogous and
+    /// complementary colors. See:
+    /// - Li-Chen Ou's Chapter 19 in Handbook of Color Psychology (2015).
+    /// - Josef Albers' Interaction of Color chapters 19 and 21.
+    ///
+    /// Implementation of Ou, Woodcock and Wright's algorithm, which uses
+    /// L*a*b* / LCH color space.
+    /// Return value has these properties:
+    /// - Values below 0 are cool, above 0 are warm.
+    /// - Lower bound: -0.52 - (chroma ^ 1.07 / 20). L*a*b* chroma is infinite.
+    ///   Assuming max of 130 chroma, -9.66.
+    /// - Upper bound: -0.52 + (chroma ^ 1.07 / 20). L*a*b* chroma is infinite.
+    ///   Assuming max of 130 chroma, 8.61.
+    pub fn raw_temperature(color: Hct) -> f64 {
+        let lab = lab_from_argb(color.to_int());
+        let hue = sanitize_degrees_double(lab[2].atan2(lab[1]) * 180.0 / PI);
+        let chroma = (lab[1] * lab[1] + lab[2] * lab[2]).sqrt();
+        -0.5 + 0.02 * chroma.powf(1.07) * (sanitize_degrees_double(hue - 50.0) * PI / 180.0).cos()
+    }
+
+    // Colors outside the cache, such as analogous colors of a different tone,
+    // have their temperature computed on the fly.
+    fn temperature_of(temps_by_hct: &IndexMap<u32, f64>, hct: Hct) -> f64 {
+        match temps_by_hct.get(&hct.to_int()) {
+            Some(temperature) => *temperature,
+            None => TemperatureCache::raw_temperature(hct),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx_eq::assert_approx_eq;
+
+    use crate::hct::Hct;
+
+    use super::TemperatureCache;
+
+    #[test]
+    fn computes_raw_temperatures_correctly() {
+        let blue_temp = TemperatureCache::raw_temperature(Hct::from_int(0xff0000ff));
+        assert_approx_eq!(-1.393, blue_temp, 0.001);
+
+        let red_temp = TemperatureCache::raw_temperature(Hct::from_int(0xffff0000));
+        assert_approx_eq!(2.351, red_temp, 0.001);
+
+        let green_temp = TemperatureCache::raw_temperature(Hct::from_int(0xff00ff00));
+        assert_approx_eq!(-0.267, green_temp, 0.001);
+
+        let white_temp = TemperatureCache::raw_temperature(Hct::from_int(0xffffffff));
+        assert_approx_eq!(-0.5, white_temp, 0.001);
+
+        let black_temp = TemperatureCache::raw_temperature(Hct::from_int(0xff000000));
+        assert_approx_eq!(-0.5, black_temp, 0.001);
+    }
+
+    #[test]
+    fn relative_temperature() {
+        let blue_temp =
+            TemperatureCache::new(Hct::from_int(0xff0000ff)).input_relative_temperature();
+        assert!(blue_temp.abs() < 0.001);
+
+        let red_temp =
+            TemperatureCache::new(Hct::from_int(0xffff0000)).input_relative_temperature();
+        assert_approx_eq!(1.0, red_temp, 0.001);
+
+        let green_temp =
+            TemperatureCache::new(Hct::from_int(0xff00ff00)).input_relative_temperature();
+        assert_approx_eq!(0.467, green_temp, 0.001);
+
+        let white_temp =
+            TemperatureCache::new(Hct::from_int(0xffffffff)).input_relative_temperature();
+        assert_approx_eq!(0.5, white_temp, 0.001);
+
+        let black_temp =
+            TemperatureCache::new(Hct::from_int(0xff000000)).input_relative_temperature();
+        assert_approx_eq!(0.5, black_temp, 0.001);
+    }
+
+    #[test]
+    fn complement() {
+        let blue_complement = TemperatureCache::new(Hct::from_int(0xff0000ff)).complement();
+        assert_eq!(0xff9d0002, blue_complement.to_int());
+
+        let red_complement = TemperatureCache::new(Hct::from_int(0xffff0000)).complement();
+        assert_eq!(0xff007bfc, red_complement.to_int());
+
+        let green_complement = TemperatureCache::new(Hct::from_int(0xff00ff00)).complement();
+        assert_eq!(0xffffd2c9, green_complement.to_int());
+
+        let white_complement = TemperatureCache::new(Hct::from_int(0xffffffff)).complement();
+        assert_eq!(0xffffffff, white_complement.to_int());
+
+        let black_complement = TemperatureCache::new(Hct::from_int(0xff000000)).complement();
+        assert_eq!(0xff000000, black_complement.to_int());
+    }
+
+    #[test]
+    fn analogous() {
+        let analogous = |argb: u32| -> Vec<u32> {
+            TemperatureCache::new(Hct::from_int(argb))
+                .analogous(5, 12)
+                .iter()
+                .map(|hct| hct.to_int())
+                .collect()
+        };
+
+        assert_eq!(
+            analogous(0xff0000ff),
+            [0xff00590c, 0xff00564e, 0xff0000ff, 0xff6700cc, 0xff81009f]
+        );
+        assert_eq!(
+            analogous(0xffff0000),
+            [0xfff60082, 0xfffc004c, 0xffff0000, 0xffd95500, 0xffaf7200]
+        );
+        assert_eq!(
+            analogous(0xff00ff00),
+            [0xffcee900, 0xff92f500, 0xff00ff00, 0xff00fd6f, 0xff00fab3]
+        );
+        assert_eq!(analogous(0xff000000), [0xff000000; 5]);
+        assert_eq!(analogous(0xffffffff), [0xffffffff; 5]);
+    }
+
+    #[test]
+    fn analogous_with_zero_count_or_divisions() {
+        let mut cache = TemperatureCache::new(Hct::from_int(0xff0000ff));
+        assert!(cache.analogous(0, 12).is_empty());
+        assert!(cache.analogous(0, 0).is_empty());
+        assert_eq!(cache.analogous(3, 0), cache.analogous(3, 1));
+        assert_eq!(cache.analogous(3, 0).len(), 3);
+    }
+}
@@ -135,6 +135,19 @@ pub fn argb_from_lstar(lstar: f64) -> u32 {
     argb_from_rgb(component, component, component)
 }
 
+/// Converts a Y value to an L* value.
+///
+/// L* in L*a*b* and Y in XYZ measure the same quantity, luminance.
+/// L* measures perceptual luminance, a linear scale. Y in XYZ
+/// measures relative luminance, a logarithmic scale.
+///
+/// `y` Y in XYZ
+///
+/// Returns L* in L*a*b*
+pub fn lstar_from_y(y: f64) -> f64 {
+    lab_f(y / 100.0) * 116.0 - 16.0
+}
+
 /// Computes the L* value of a color in ARGB representation.
 ///
 ///
@@ -215,14 +228,49 @@ fn lab_inv_f(ft: f64) -> f64 {
     }
 }
 
+/// Converts a color from ARGB representation to Oklab representation, with
+/// L in 0..=1.
+pub fn oklab_from_argb(argb: u32) -> [f64; 3] {
+    let linear_r = linearized(red_from_argb(argb)) / 100.0;
+    let linear_g = linearized(green_from_argb(argb)) / 100.0;
+    let linear_b = linearized(blue_from_argb(argb)) / 100.0;
+    let l = 0.4122214708 * linear_r + 0.5363325363 * linear_g + 0.0514459929 * linear_b;
+    let m = 0.2119034982 * linear_r + 0.6806995451 * linear_g + 0.1073969566 * linear_b;
+    let s = 0.0883024619 * linear_r + 0.2817188376 * linear_g + 0.6299787005 * linear_b;
+    let l = l.cbrt();
+    let m = m.cbrt();
+    let s = s.cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// Converts a color represented in Oklab color space into an ARGB
+/// integer.
+pub fn argb_from_oklab(l: f64, a: f64, b: f64) -> u32 {
+    let l_ = l + 0.3963377774 * a + 0.2158037573 * b;
+    let m_ = l - 0.1055613458 * a - 0.0638541728 * b;
+    let s_ = l - 0.0894841775 * a - 1.2914855480 * b;
+    let l = l_ * l_ * l_;
+    let m = m_ * m_ * m_;
+    let s = s_ * s_ * s_;
+    argb_from_linrgb([
+        (4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s) * 100.0,
+        (-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s) * 100.0,
+        (-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s) * 100.0,
+    ])
+}
+
 #[cfg(test)]
 mod test {
     use approx_eq::assert_approx_eq;
 
     use crate::utils::color_utils::{
-        argb_from_lab, argb_from_lstar, argb_from_rgb, argb_from_xyz, blue_from_argb, delinearized,
-        green_from_argb, lab_from_argb, linearized, lstar_from_argb, red_from_argb, xyz_from_argb,
-        y_from_lstar,
+        argb_from_lab, argb_from_lstar, argb_from_oklab, argb_from_rgb, argb_from_xyz,
+        blue_from_argb, delinearized, green_from_argb, lab_from_argb, linearized, lstar_from_argb,
+        lstar_from_y, oklab_from_argb, red_from_argb, xyz_from_argb, y_from_lstar,
     };
 
     fn _lstar_from_y(y: f64) -> f64 {
@@ -257,8 +305,8 @@ mod test {
     #[test]
     fn range_integrity() {
         let range = _range(3.0, 9999.0, 1234);
-        for i in 0..1234 {
-            assert_approx_eq!(range[i], 3.0 + 8.1070559611 * i as f64, 1e-5);
+        for (i, value) in range.iter().enumerate() {
+            assert_approx_eq!(*value, 3.0 + 8.1070559611 * i as f64, 1e-5);
         }
     }
 
@@ -278,6 +326,13 @@ mod test {
         }
     }
 
+    #[test]
+    fn lstar_from_y_matches_reference() {
+        for y in _range(0.0, 100.0, 1001) {
+            assert_approx_eq!(lstar_from_y(y), _lstar_from_y(y), 1e-5);
+        }
+    }
+
     #[test]
     fn y_continuity() {
         let delta = 1e-8;
@@ -326,6 +381,34 @@ mod test {
         }
     }
 
+    #[test]
+    fn rgb_to_oklab_to_rgb() {
+        let r_range = rgb_range();
+        let g_range = r_range.clone();
+        let b_range = r_range.clone();
+        for r in r_range {
+            for g in &g_range {
+                for b in &b_range {
+                    let argb = argb_from_rgb(r, *g, *b);
+                    let oklab = oklab_from_argb(argb);
+                    let converted = argb_from_oklab(oklab[0], oklab[1], oklab[2]);
+                    assert_approx_eq!(red_from_argb(converted) as f64, r as f64, 1.5);
+                    assert_approx_eq!(green_from_argb(converted) as f64, *g as f64, 1.5);
+                    assert_approx_eq!(blue_from_argb(converted) as f64, *b as f64, 1.5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn oklab_of_white_and_black() {
+        let white = oklab_from_argb(0xffffffff);
+        assert_approx_eq!(white[0], 1.0, 1e-4);
+        assert!(white[1].abs() < 1e-4);
+        assert!(white[2].abs() < 1e-4);
+        assert_eq!(oklab_from_argb(0xff000000), [0.0, 0.0, 0.0]);
+    }
+
     #[test]
     fn rgb_to_lstar_to_rgb() {
         let rgb_range = full_rgb_range();
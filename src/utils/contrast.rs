@@ -0,0 +1,241 @@
+//! Utility methods for calculating contrast given two colors, or calculating
+//! a color given one color and a contrast ratio.
+//!
+//! Contrast ratio is calculated using XYZ's Y. When linearized to match human
+//! perception, Y becomes HCT's tone and L*a*b*'s' L*. Informally, this is the
+//! lightness of a color.
+//!
+//! Methods refer to tone, T in the HCT color space.
+//! Tone is equivalent to L* in the L*a*b* color space, or L in the LCH color
+//! space.
+
+use super::{
+    color_utils::{lstar_from_argb, lstar_from_y, y_from_lstar},
+    math_utils::clamp_double,
+};
+
+/// Contrast ratio is a measure of legibility, its used to compare the
+/// lightness of two colors. This method is used commonly in industry due to
+/// its use by WCAG.
+///
+/// To compare lightness, the colors are expressed in the XYZ color space,
+/// where Y is lightness, also known as relative luminance.
+///
+/// The equation is ratio = lighter Y + 5 / darker Y + 5.
+pub fn ratio_of_ys(y1: f64, y2: f64) -> f64 {
+    let lighter = y1.max(y2);
+    let darker = if lighter == y2 { y1 } else { y2 };
+    (lighter + 5.0) / (darker + 5.0)
+}
+
+/// Contrast ratio of two tones. T in HCT, L* in L*a*b*. Also known as
+/// luminance or perpectual luminance.
+///
+/// Contrast ratio is defined using Y in XYZ, relative luminance. However,
+/// relative luminance is linear to number of photons, not to perception of
+/// lightness. Perceptual luminance, L* in L*a*b*, T in HCT, is. Designers
+/// prefer color spaces with perceptual luminance since they're accurate to
+/// the eye.
+///
+/// Y and L* are pure functions of each other, so it possible to use
+/// perceptually accurate color spaces, and measure contrast, and measure
+/// contrast in a much more understandable way: instead of a ratio, a linear
+/// difference. This allows a designer to determine what they need to adjust a
+/// color's lightness to in order to reach their desired contrast, instead of
+/// guessing & checking with hex codes.
+pub fn ratio_of_tones(tone_a: f64, tone_b: f64) -> f64 {
+    let tone_a = clamp_double(0.0, 100.0, tone_a);
+    let tone_b = clamp_double(0.0, 100.0, tone_b);
+    ratio_of_ys(y_from_lstar(tone_a), y_from_lstar(tone_b))
+}
+
+/// Contrast ratio of two colors in ARGB format.
+///
+/// The alpha channel is ignored; both colors are treated as opaque.
+pub fn ratio_of_argb(argb_a: u32, argb_b: u32) -> f64 {
+    ratio_of_tones(lstar_from_argb(argb_a), lstar_from_argb(argb_b))
+}
+
+/// Returns T in HCT, L* in L*a*b* >= `tone` parameter that ensures `ratio`
+/// with input T/L*. Returns -1 if `ratio` cannot be achieved.
+///
+/// `tone` Tone return value must contrast with.
+/// `ratio` Desired contrast ratio of return value and tone parameter.
+pub fn lighter(tone: f64, ratio: f64) -> f64 {
+    if !(0.0..=100.0).contains(&tone) {
+        return -1.0;
+    }
+    // Invert the contrast ratio equation to determine lighter Y given a ratio
+    // and darker Y.
+    let dark_y = y_from_lstar(tone);
+    let light_y = ratio * (dark_y + 5.0) - 5.0;
+    if !(0.0..=100.0).contains(&light_y) {
+        return -1.0;
+    }
+    let real_contrast = ratio_of_ys(light_y, dark_y);
+    let delta = (real_contrast - ratio).abs();
+    if real_contrast < ratio && delta > CONTRAST_RATIO_EPSILON {
+        return -1.0;
+    }
+
+    let return_value = lstar_from_y(light_y) + LUMINANCE_GAMUT_MAP_TOLERANCE;
+    if !(0.0..=100.0).contains(&return_value) {
+        return -1.0;
+    }
+    return_value
+}
+
+/// Tone >= `tone` parameter that ensures `ratio`. 100 if `ratio` cannot be
+/// achieved.
+///
+/// This method is unsafe because the returned value is guaranteed to be in
+/// bounds, but, the in bounds return value may not reach the desired ratio.
+///
+/// `tone` Tone return value must contrast with.
+/// `ratio` Desired contrast ratio of return value and tone parameter.
+pub fn lighter_unsafe(tone: f64, ratio: f64) -> f64 {
+    let lighter_safe = lighter(tone, ratio);
+    if lighter_safe < 0.0 {
+        100.0
+    } else {
+        lighter_safe
+    }
+}
+
+/// Returns T in HCT, L* in L*a*b* <= `tone` parameter that ensures `ratio`
+/// with input T/L*. Returns -1 if `ratio` cannot be achieved.
+///
+/// `tone` Tone return value must contrast with.
+/// `ratio` Desired contrast ratio of return value and tone parameter.
+pub fn darker(tone: f64, ratio: f64) -> f64 {
+    if !(0.0..=100.0).contains(&tone) {
+        return -1.0;
+    }
+    // Invert the contrast ratio equation to determine darker Y given a ratio
+    // and lighter Y.
+    let light_y = y_from_lstar(tone);
+    let dark_y = ((light_y + 5.0) / ratio) - 5.0;
+    if !(0.0..=100.0).contains(&dark_y) {
+        return -1.0;
+    }
+    let real_contrast = ratio_of_ys(light_y, dark_y);
+    let delta = (real_contrast - ratio).abs();
+    if real_contrast < ratio && delta > CONTRAST_RATIO_EPSILON {
+        return -1.0;
+    }
+
+    let return_value = lstar_from_y(dark_y) - LUMINANCE_GAMUT_MAP_TOLERANCE;
+    if !(0.0..=100.0).contains(&return_value) {
+        return -1.0;
+    }
+    return_value
+}
+
+/// Tone <= `tone` parameter that ensures `ratio`. 0 if `ratio` cannot be
+/// achieved.
+///
+/// This method is unsafe because the returned value is guaranteed to be in
+/// bounds, but, the in bounds return value may not reach the desired ratio.
+///
+/// `tone` Tone return value must contrast with.
+/// `ratio` Desired contrast ratio of return value and tone parameter.
+pub fn darker_unsafe(tone: f64, ratio: f64) -> f64 {
+    let darker_safe = darker(tone, ratio);
+    if darker_safe < 0.0 {
+        0.0
+    } else {
+        darker_safe
+    }
+}
+
+// The tolerance used when comparing a computed contrast ratio against the
+// requested one.
+const CONTRAST_RATIO_EPSILON: f64 = 0.04;
+
+// Color spaces that measure luminance, such as Y in XYZ, L* in L*a*b*, or T in
+// HCT, are known as perceptually accurate color spaces.
+//
+// To be displayed, they must gamut map to a "display space", one that has a
+// defined limit on the number of colors. Display spaces include sRGB, more
+// commonly understood as RGB/HSL/HSV/HSB.
+//
+// Gamut mapping is undefined and not defined by the color space. Any gamut
+// mapping algorithm must choose how to sacrifice accuracy in hue, saturation,
+// and/or lightness.
+//
+// A principled solution is to maintain lightness, thus maintaining contrast
+// ratio, and that's what HCT does. However, the gamut mapping cannot be
+// perfect, and the lightness may drift slightly. This tolerance keeps the
+// returned tone far enough away to still reach the desired ratio after
+// gamut mapping.
+const LUMINANCE_GAMUT_MAP_TOLERANCE: f64 = 0.4;
+
+#[cfg(test)]
+mod test {
+    use approx_eq::assert_approx_eq;
+
+    use super::{darker, darker_unsafe, lighter, lighter_unsafe, ratio_of_argb, ratio_of_tones};
+
+    #[test]
+    fn ratio_of_tones_out_of_bounds_input() {
+        assert_approx_eq!(ratio_of_tones(-10.0, 110.0), 21.0, 0.001);
+    }
+
+    #[test]
+    fn lighter_impossible_ratio_errors() {
+        assert_eq!(lighter(90.0, 10.0), -1.0);
+    }
+
+    #[test]
+    fn lighter_out_of_bounds_input_above_errors() {
+        assert_eq!(lighter(110.0, 2.0), -1.0);
+    }
+
+    #[test]
+    fn lighter_out_of_bounds_input_below_errors() {
+        assert_eq!(lighter(-10.0, 2.0), -1.0);
+    }
+
+    #[test]
+    fn lighter_unsafe_returns_max_tone() {
+        assert_eq!(lighter_unsafe(100.0, 2.0), 100.0);
+    }
+
+    #[test]
+    fn darker_impossible_ratio_errors() {
+        assert_eq!(darker(10.0, 20.0), -1.0);
+    }
+
+    #[test]
+    fn darker_out_of_bounds_input_above_errors() {
+        assert_eq!(darker(110.0, 2.0), -1.0);
+    }
+
+    #[test]
+    fn darker_out_of_bounds_input_below_errors() {
+        assert_eq!(darker(-10.0, 2.0), -1.0);
+    }
+
+    #[test]
+    fn darker_unsafe_returns_min_tone() {
+        assert_eq!(darker_unsafe(0.0, 2.0), 0.0);
+    }
+
+    #[test]
+    fn lighter_and_darker_reach_ratio() {
+        for tone in [40.0, 50.0, 60.0] {
+            let light = lighter(tone, 3.0);
+            assert!(light >= 0.0);
+            assert!(ratio_of_tones(light, tone) >= 3.0);
+            let dark = darker(tone, 3.0);
+            assert!(dark >= 0.0);
+            assert!(ratio_of_tones(dark, tone) >= 3.0);
+        }
+    }
+
+    #[test]
+    fn ratio_of_argb_black_and_white() {
+        assert_approx_eq!(ratio_of_argb(0xff000000, 0xffffffff), 21.0, 0.001);
+        assert_approx_eq!(ratio_of_argb(0xff808080, 0xff808080), 1.0, 0.001);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{io::Cursor, path::Path};
+
+pub use image::ImageError;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+
+use crate::quantize::{Downsampler, PixelBuffer, PixelLayout, SamplingMethod};
+
+use super::{
+    image_utils::source_color_from_pixels,
+    theme_utils::{theme_from_pixels, Theme},
+};
+
+/// An image decoded into ARGB rows, top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedImage {
+    pub pixels: Vec<u32>,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Decodes a PNG, JPEG or WebP image, detecting the format from its contents.
+/// Images without alpha are opaque, and EXIF orientation is applied so the
+/// pixels appear upright.
+pub fn decode_image(bytes: &[u8]) -> Result<DecodedImage, ImageError> {
+    decode(ImageReader::new(Cursor::new(bytes)).with_guessed_format()?)
+}
+
+/// Decodes a PNG, JPEG or WebP file. See [`decode_image`].
+pub fn decode_image_file(path: impl AsRef<Path>) -> Result<DecodedImage, ImageError> {
+    decode(ImageReader::open(path)?.with_guessed_format()?)
+}
+
+fn decode<R: std::io::BufRead + std::io::Seek>(
+    reader: ImageReader<R>,
+) -> Result<DecodedImage, ImageError> {
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    let rgba = image.into_rgba8();
+    let (width, height) = (rgba.width() as usize, rgba.height() as usize);
+    let buffer = PixelBuffer::packed(rgba.as_raw(), width, height, PixelLayout::Rgba8)
+        .expect("decoded images are packed RGBA");
+    Ok(DecodedImage {
+        pixels: buffer.to_argb(),
+        width,
+        height,
+    })
+}
+
+/// The image's pixels, reduced to [`Downsampler::DEFAULT_MAX_PIXELS`] the way
+/// upstream pipelines resize wallpapers before extracting colors. Blocks
+/// average only their opaque pixels, since extraction ignores the rest.
+fn downsampled_pixels(image: &DecodedImage) -> Vec<u32> {
+    Downsampler::new(
+        SamplingMethod::OpaqueAverage,
+        Downsampler::DEFAULT_MAX_PIXELS,
+    )
+    .expect("the default budget is positive")
+    .downsample(&image.pixels, image.width, image.height)
+    .expect("decoded images have width * height pixels")
+    .pixels
+}
+
+/// The source color of an encoded image. See [`source_color_from_pixels`].
+pub fn source_color_from_image(bytes: &[u8]) -> Result<u32, ImageError> {
+    let image = decode_image(bytes)?;
+    Ok(source_color_from_pixels(&downsampled_pixels(&image)))
+}
+
+/// The source color of an image file. See [`source_color_from_pixels`].
+pub fn source_color_from_image_file(path: impl AsRef<Path>) -> Result<u32, ImageError> {
+    let image = decode_image_file(path)?;
+    Ok(source_color_from_pixels(&downsampled_pixels(&image)))
+}
+
+/// A theme for an encoded image. See [`theme_from_pixels`].
+pub fn theme_from_image(bytes: &[u8]) -> Result<Theme, ImageError> {
+    let image = decode_image(bytes)?;
+    Ok(theme_from_pixels(&downsampled_pixels(&image)))
+}
+
+/// A theme for an image file. See [`theme_from_pixels`].
+pub fn theme_from_image_file(path: impl AsRef<Path>) -> Result<Theme, ImageError> {
+    let image = decode_image_file(path)?;
+    Ok(theme_from_pixels(&downsampled_pixels(&image)))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use image::{ImageFormat, Rgb, RgbImage, Rgba, RgbaImage};
+
+    use super::{decode_image, decode_image_file, source_color_from_image, theme_from_image_file};
+
+    fn encode(image: impl Into<image::DynamicImage>, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = vec![];
+        image
+            .into()
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap();
+        bytes
+    }
+
+    fn translucent_fixture() -> RgbaImage {
+        RgbaImage::from_fn(3, 2, |x, y| match (x, y) {
+            (0, 0) => Rgba([0xff, 0x00, 0x00, 0xff]),
+            (1, 0) => Rgba([0x00, 0xff, 0x00, 0x80]),
+            (2, 0) => Rgba([0x00, 0x00, 0xff, 0x00]),
+            _ => Rgba([0x12, 0x34, 0x56, 0xff]),
+        })
+    }
+
+    #[test]
+    fn decodes_png_and_webp_with_alpha() {
+        for format in [ImageFormat::Png, ImageFormat::WebP] {
+            let image = decode_image(&encode(translucent_fixture(), format)).unwrap();
+            assert_eq!((image.width, image.height), (3, 2));
+            assert_eq!(image.pixels[..2], [0xffff0000, 0x8000ff00], "{format:?}");
+            assert_eq!(image.pixels[2] >> 24, 0, "{format:?}");
+            assert_eq!(image.pixels[3..], [0xff123456; 3], "{format:?}");
+        }
+    }
+
+    #[test]
+    fn applies_jpeg_orientation() {
+        let fixture = RgbImage::from_fn(16, 8, |x, _| match x < 8 {
+            true => Rgb([0xff, 0x00, 0x00]),
+            false => Rgb([0x00, 0x00, 0xff]),
+        });
+        let jpeg = encode(fixture, ImageFormat::Jpeg);
+        // An EXIF segment with orientation 6: rotate 90° clockwise to display.
+        let mut exif = vec![0xff, 0xe1, 0x00, 0x22];
+        exif.extend_from_slice(b"Exif\0\0MM\0\x2a\0\0\0\x08");
+        exif.extend_from_slice(&[0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0]);
+        let oriented = [&jpeg[..2], &exif, &jpeg[2..]].concat();
+
+        let image = decode_image(&oriented).unwrap();
+        assert_eq!((image.width, image.height), (8, 16));
+        let red = |pixel: u32| (pixel >> 16) & 0xff > 0xc0 && pixel & 0xff < 0x40;
+        assert!(red(image.pixels[0]));
+        assert!(!red(image.pixels[image.pixels.len() - 1]));
+    }
+
+    #[test]
+    fn feeds_extraction_pipeline() {
+        let fixture = RgbaImage::from_pixel(300, 200, Rgba([0x00, 0x00, 0xff, 0xff]));
+        let png = encode(fixture, ImageFormat::Png);
+        assert_eq!(source_color_from_image(&png).unwrap(), 0xff0000ff);
+
+        let path = std::env::temp_dir().join(format!("material3-{}.png", std::process::id()));
+        std::fs::write(&path, &png).unwrap();
+        let decoded = decode_image_file(&path);
+        let theme = theme_from_image_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(decoded.unwrap().pixels.len(), 300 * 200);
+        assert_eq!(theme.unwrap().source, 0xff0000ff);
+    }
+
+    #[test]
+    fn keeps_blocks_with_translucent_edges() {
+        // Blue bars whose right edge is anti-aliased, so every block the
+        // downsampler reduces to one pixel holds a translucent pixel, beside
+        // a smaller opaque red area.
+        let fixture = RgbaImage::from_fn(280, 280, |x, _| match (x < 180, x % 3) {
+            (true, 2) => Rgba([0x00, 0x00, 0xff, 0x80]),
+            (true, _) => Rgba([0x00, 0x00, 0xff, 0xff]),
+            (false, _) => Rgba([0xff, 0x00, 0x00, 0xff]),
+        });
+        let png = encode(fixture, ImageFormat::Png);
+        assert_eq!(source_color_from_image(&png).unwrap(), 0xff0000ff);
+    }
+
+    #[test]
+    fn reports_undecodable_input() {
+        assert!(decode_image(b"not an image").is_err());
+        assert!(decode_image_file("/nonexistent/wallpaper.png").is_err());
+    }
+}
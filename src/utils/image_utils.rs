@@ -0,0 +1,105 @@
+use crate::{
+    quantize::{Quantizer, QuantizerCelebi, QuantizerInput},
+    score::score,
+};
+
+/// Settings for ranking source colors from an image. The defaults match
+/// upstream's `sourceColorFromImage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceColorOptions {
+    /// The number of colors [`QuantizerCelebi`] reduces the image to. Defaults
+    /// to 128.
+    pub max_colors: u32,
+    /// The maximum number of ranked colors to return. Defaults to 4.
+    pub desired: usize,
+    /// Whether [`score`] removes colors unsuitable for a theme. Defaults to
+    /// true.
+    pub filter: bool,
+}
+
+impl Default for SourceColorOptions {
+    fn default() -> Self {
+        SourceColorOptions {
+            max_colors: 128,
+            desired: 4,
+            filter: true,
+        }
+    }
+}
+
+/// The color best suited to be the source of a theme for an image, from its
+/// ARGB `pixels`. Pixels that aren't opaque are ignored.
+///
+/// Every pixel is quantized, so large images should first be reduced with a
+/// [`Downsampler`](crate::quantize::Downsampler).
+pub fn source_color_from_pixels(pixels: &[u32]) -> u32 {
+    source_colors_from_input(&QuantizerInput::new(pixels), &SourceColorOptions::default())[0]
+}
+
+/// Colors suitable to be the source of a theme for `input`, most suitable
+/// first. There is always at least one, and no more than `options.desired`
+/// unless it is 0. Unless `input` sets an alpha threshold, pixels that aren't
+/// opaque are ignored.
+pub fn source_colors_from_input(input: &QuantizerInput, options: &SourceColorOptions) -> Vec<u32> {
+    let input = match input.alpha_threshold() {
+        Some(_) => *input,
+        None => input.with_alpha_threshold(255),
+    };
+    let result = QuantizerCelebi::default().quantize_input(&input, options.max_colors.max(1));
+    score(result.color_to_count, options.desired, options.filter)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{hct::Hct, quantize::QuantizerInput};
+
+    use super::{source_color_from_pixels, source_colors_from_input, SourceColorOptions};
+
+    const RED: u32 = 0xffff0000;
+    const BLUE: u32 = 0xff0000ff;
+    const GOOGLE_BLUE: u32 = 0xff4285f4;
+
+    #[test]
+    fn dominant_color_is_source() {
+        let mut pixels = vec![BLUE; 300];
+        pixels.extend(vec![RED; 100]);
+        assert_eq!(source_color_from_pixels(&pixels), BLUE);
+    }
+
+    #[test]
+    fn ignores_translucent_pixels() {
+        let mut pixels = vec![0x80ff0000; 300];
+        pixels.extend(vec![BLUE; 100]);
+        assert_eq!(source_color_from_pixels(&pixels), BLUE);
+    }
+
+    #[test]
+    fn falls_back_to_google_blue() {
+        assert_eq!(source_color_from_pixels(&[]), GOOGLE_BLUE);
+        assert_eq!(source_color_from_pixels(&[0xff808080; 10]), GOOGLE_BLUE);
+    }
+
+    #[test]
+    fn ranks_weighted_input() {
+        let pixels = [BLUE, RED];
+        let input = QuantizerInput::new(&pixels).with_weights(&[1, 3]).unwrap();
+        let options = SourceColorOptions {
+            desired: 2,
+            ..SourceColorOptions::default()
+        };
+        assert_eq!(source_colors_from_input(&input, &options), vec![RED, BLUE]);
+    }
+
+    #[test]
+    fn returns_at_most_desired() {
+        let pixels: Vec<u32> = (0..12)
+            .map(|i| Hct::new(i as f64 * 30.0, 60.0, 50.0).to_int())
+            .collect();
+        let options = SourceColorOptions {
+            desired: 2,
+            ..SourceColorOptions::default()
+        };
+        let colors = source_colors_from_input(&QuantizerInput::new(&pixels), &options);
+        assert_eq!(colors.len(), 2);
+    }
+}
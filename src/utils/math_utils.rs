@@ -107,41 +107,3 @@ pub fn rotation_direction(from: f64, to: f64) -> f64 {
 pub fn calculate_difference_degrees(a: f64, b: f64) -> f64 {
     180.0 - ((a - b).abs() - 180.0).abs()
 }
-
-#[cfg(test)]
-mod test {
-    /// Sign of direction change needed to travel from one angle to another.
-    ///
-    /// `from` is the angle travel starts from in degrees. `to` is the ending
-    /// angle, also in degrees.
-    ///
-    /// The return value is -1 if decreasing `from` leads to the shortest travel
-    /// distance,  1 if increasing from leads to the shortest travel distance.
-    fn rotation_direction(from: f64, to: f64) -> f64 {
-        let a = to - from;
-        let b = to - from + 360.0;
-        let c = to - from - 360.0;
-
-        let a_abs = a.abs();
-        let b_abs = b.abs();
-        let c_abs = c.abs();
-
-        if a_abs <= b_abs && a_abs <= c_abs {
-            if a >= 0.0 {
-                1.0
-            } else {
-                -1.0
-            }
-        } else if b_abs <= a_abs && b_abs <= c_abs {
-            if b >= 0.0 {
-                1.0
-            } else {
-                -1.0
-            }
-        } else if c >= 0.0 {
-            1.0
-        } else {
-            -1.0
-        }
-    }
-}
@@ -1,2 +1,7 @@
 pub mod color_utils;
+pub mod contrast;
+#[cfg(feature = "image")]
+pub mod image_file;
+pub mod image_utils;
 pub mod math_utils;
+pub mod theme_utils;
@@ -0,0 +1,60 @@
+use crate::{quantize::QuantizerInput, scheme::Scheme};
+
+use super::image_utils::{source_colors_from_input, SourceColorOptions};
+
+/// Light and dark schemes generated from the colors of an image.
+pub struct Theme {
+    /// The color the schemes are generated from, the first of `seeds`.
+    pub source: u32,
+    /// Colors suitable to be the source of a theme, most suitable first.
+    pub seeds: Vec<u32>,
+    pub light: Scheme,
+    pub dark: Scheme,
+}
+
+impl Theme {
+    /// A theme whose schemes are generated from the first of `seeds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seeds` is empty.
+    pub fn from_seeds(seeds: Vec<u32>) -> Theme {
+        let source = seeds[0];
+        Theme {
+            source,
+            seeds,
+            light: Scheme::light(source),
+            dark: Scheme::dark(source),
+        }
+    }
+}
+
+/// A theme for an image from its ARGB `pixels`, with upstream's defaults. See
+/// [`source_color_from_pixels`](super::image_utils::source_color_from_pixels).
+pub fn theme_from_pixels(pixels: &[u32]) -> Theme {
+    theme_from_input(&QuantizerInput::new(pixels), &SourceColorOptions::default())
+}
+
+/// A theme for an image from weighted or masked pixels. See
+/// [`source_colors_from_input`].
+pub fn theme_from_input(input: &QuantizerInput, options: &SourceColorOptions) -> Theme {
+    Theme::from_seeds(source_colors_from_input(input, options))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scheme::Scheme;
+
+    use super::theme_from_pixels;
+
+    #[test]
+    fn schemes_come_from_source() {
+        let mut pixels = vec![0xff0000ff; 300];
+        pixels.extend(vec![0xffff0000; 100]);
+        let theme = theme_from_pixels(&pixels);
+        assert_eq!(theme.source, 0xff0000ff);
+        assert_eq!(theme.seeds[0], theme.source);
+        assert_eq!(theme.light.primary, Scheme::light(theme.source).primary);
+        assert_eq!(theme.dark.primary, Scheme::dark(theme.source).primary);
+    }
+}